@@ -28,6 +28,10 @@ mod program;
 #[doc(inline)]
 pub use program::*;
 
+mod program_cache;
+#[doc(inline)]
+pub use program_cache::*;
+
 mod scissor_box;
 #[doc(inline)]
 pub use scissor_box::*;
@@ -59,6 +63,10 @@ pub enum CoreError {
     ShaderCompilation(String, String, String),
     #[error("failed to link shader program: {0}")]
     ShaderLink(String),
+    #[error("failed creating program binary cache directory {0}: {1}")]
+    ProgramBinaryCache(String, std::io::Error),
+    #[error("program binaries are not supported by the graphics driver")]
+    ProgramBinaryNotSupported,
 }
 
 pub(crate) fn full_screen_draw(
@@ -111,15 +119,40 @@ fn from_byte_slice<T: DataType>(data: &[u8]) -> &[T] {
 }
 
 fn format_from_data_type<T: DataType>() -> u32 {
-    match T::size() {
-        1 => crate::context::RED,
-        2 => crate::context::RG,
-        3 => crate::context::RGB,
-        4 => crate::context::RGBA,
-        _ => unreachable!(),
+    if is_integer_data_type::<T>() {
+        match T::size() {
+            1 => crate::context::RED_INTEGER,
+            2 => crate::context::RG_INTEGER,
+            3 => crate::context::RGB_INTEGER,
+            4 => crate::context::RGBA_INTEGER,
+            _ => unreachable!(),
+        }
+    } else {
+        match T::size() {
+            1 => crate::context::RED,
+            2 => crate::context::RG,
+            3 => crate::context::RGB,
+            4 => crate::context::RGBA,
+            _ => unreachable!(),
+        }
     }
 }
 
+///
+/// Returns whether the data type is stored as unnormalized integers on the GPU.
+/// Note that `u8` is not an integer data type in this sense since it is normalized to the range `[0, 1]`.
+///
+fn is_integer_data_type<T: DataType>() -> bool {
+    matches!(
+        T::data_type(),
+        crate::context::UNSIGNED_SHORT
+            | crate::context::UNSIGNED_INT
+            | crate::context::BYTE
+            | crate::context::SHORT
+            | crate::context::INT
+    )
+}
+
 fn flip_y<T: TextureDataType>(pixels: &mut [T], width: usize, height: usize) {
     for row in 0..height / 2 {
         for col in 0..width {
@@ -15,7 +15,10 @@ pub use crate::context::HasContext;
 pub struct Context {
     context: Arc<crate::context::Context>,
     /// A cache of programs to avoid recompiling a [Program] every frame.
-    pub programs: Arc<RwLock<HashMap<Vec<u8>, Program>>>,
+    pub programs: Arc<RwLock<ProgramCache>>,
+    pub(crate) shared_uniform_buffers: Arc<RwLock<HashMap<String, UniformBuffer>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    program_binary_cache_directory: Arc<RwLock<Option<std::path::PathBuf>>>,
 }
 
 impl Context {
@@ -37,12 +40,49 @@ impl Context {
         let c = unsafe {
             Self {
                 context,
-                programs: Arc::new(RwLock::new(HashMap::new())),
+                programs: Arc::new(RwLock::new(ProgramCache::new())),
+                shared_uniform_buffers: Arc::new(RwLock::new(HashMap::new())),
+                #[cfg(not(target_arch = "wasm32"))]
+                program_binary_cache_directory: Arc::new(RwLock::new(None)),
             }
         };
         Ok(c)
     }
 
+    ///
+    /// Enables the on-disk cache of program binaries in the given directory or disables it if `None` is given. The cache is disabled by default.
+    /// When enabled, [Program::from_source] stores the binary of each compiled program in the directory, keyed by the shader source and the driver,
+    /// and the next time a program with the same source is created, for example when the application is restarted, the binary is loaded instead of compiling the source.
+    /// If the driver rejects a stored binary, for example after a driver update, the program is compiled and stored again.
+    ///
+    /// The directory is created if it does not exist. Not available on web.
+    /// Returns an error if the directory cannot be created or if the context does not support program binaries,
+    /// which requires OpenGL 4.1, OpenGL ES 3.0 or the `GL_ARB_get_program_binary` extension and at least one program binary format.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_program_binary_cache_directory(
+        &self,
+        directory: Option<std::path::PathBuf>,
+    ) -> Result<(), CoreError> {
+        if let Some(directory) = &directory {
+            if !is_program_binary_supported(self) {
+                Err(CoreError::ProgramBinaryNotSupported)?;
+            }
+            std::fs::create_dir_all(directory)
+                .map_err(|e| CoreError::ProgramBinaryCache(directory.display().to_string(), e))?;
+        }
+        *self.program_binary_cache_directory.write().unwrap() = directory;
+        Ok(())
+    }
+
+    ///
+    /// Returns the directory of the on-disk cache of program binaries if it is enabled, see [Self::set_program_binary_cache_directory].
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn program_binary_cache_directory(&self) -> Option<std::path::PathBuf> {
+        self.program_binary_cache_directory.read().unwrap().clone()
+    }
+
     ///
     /// Set the scissor test for this context (see [ScissorBox]).
     ///
@@ -182,6 +222,61 @@ impl Context {
         }
     }
 
+    ///
+    /// Set the stencil test for this context (see [StencilTest]).
+    ///
+    pub fn set_stencil_test(&self, stencil_test: StencilTest) {
+        unsafe {
+            if let StencilTest::Enabled {
+                function,
+                reference,
+                read_mask,
+                write_mask,
+                stencil_fail,
+                depth_fail,
+                pass,
+            } = stencil_test
+            {
+                self.enable(crate::context::STENCIL_TEST);
+                self.stencil_func(
+                    match function {
+                        StencilFunction::Never => crate::context::NEVER,
+                        StencilFunction::Less => crate::context::LESS,
+                        StencilFunction::Equal => crate::context::EQUAL,
+                        StencilFunction::LessOrEqual => crate::context::LEQUAL,
+                        StencilFunction::Greater => crate::context::GREATER,
+                        StencilFunction::NotEqual => crate::context::NOTEQUAL,
+                        StencilFunction::GreaterOrEqual => crate::context::GEQUAL,
+                        StencilFunction::Always => crate::context::ALWAYS,
+                    },
+                    reference as i32,
+                    read_mask as u32,
+                );
+                self.stencil_mask(write_mask as u32);
+                self.stencil_op(
+                    Self::stencil_const_from_op(stencil_fail),
+                    Self::stencil_const_from_op(depth_fail),
+                    Self::stencil_const_from_op(pass),
+                );
+            } else {
+                self.disable(crate::context::STENCIL_TEST);
+            }
+        }
+    }
+
+    fn stencil_const_from_op(op: StencilOp) -> u32 {
+        match op {
+            StencilOp::Keep => crate::context::KEEP,
+            StencilOp::Zero => crate::context::ZERO,
+            StencilOp::Replace => crate::context::REPLACE,
+            StencilOp::Increment => crate::context::INCR,
+            StencilOp::IncrementWrap => crate::context::INCR_WRAP,
+            StencilOp::Decrement => crate::context::DECR,
+            StencilOp::DecrementWrap => crate::context::DECR_WRAP,
+            StencilOp::Invert => crate::context::INVERT,
+        }
+    }
+
     fn blend_const_from_multiplier(multiplier: BlendMultiplierType) -> u32 {
         match multiplier {
             BlendMultiplierType::Zero => crate::context::ZERO,
@@ -219,6 +314,7 @@ impl Context {
             self.set_depth_test(render_states.depth_test);
         }
         self.set_blend(render_states.blend);
+        self.set_stencil_test(render_states.stencil_test);
     }
 
     ///
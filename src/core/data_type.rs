@@ -611,6 +611,9 @@ impl<T: PrimitiveDataType> DataType for Matrix4<T> {
 
 pub trait DepthDataType {
     fn internal_format() -> u32;
+    fn has_stencil() -> bool {
+        false
+    }
 }
 
 impl DepthDataType for f16 {
@@ -628,3 +631,19 @@ impl DepthDataType for f32 {
         crate::context::DEPTH_COMPONENT32F
     }
 }
+impl DepthDataType for f24s8 {
+    fn internal_format() -> u32 {
+        crate::context::DEPTH24_STENCIL8
+    }
+    fn has_stencil() -> bool {
+        true
+    }
+}
+impl DepthDataType for f32s8 {
+    fn internal_format() -> u32 {
+        crate::context::DEPTH32F_STENCIL8
+    }
+    fn has_stencil() -> bool {
+        true
+    }
+}
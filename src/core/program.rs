@@ -27,6 +27,26 @@ impl Program {
         fragment_shader_source: &str,
     ) -> Result<Self, CoreError> {
         unsafe {
+            let header = shader_header(context);
+            let vertex_shader_source = format!("{}{}", header, vertex_shader_source);
+            let fragment_shader_source = format!("{}{}", header, fragment_shader_source);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let binary_path = context.program_binary_cache_directory().map(|directory| {
+                directory.join(program_binary_file_name(
+                    context,
+                    &vertex_shader_source,
+                    &fragment_shader_source,
+                ))
+            });
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(id) = binary_path
+                .as_ref()
+                .and_then(|path| load_program_binary(context, path))
+            {
+                return Ok(Self::from_linked_program(context, id));
+            }
+
             let vert_shader = context
                 .create_shader(crate::context::VERTEX_SHADER)
                 .expect("Failed creating vertex shader");
@@ -34,25 +54,6 @@ impl Program {
                 .create_shader(crate::context::FRAGMENT_SHADER)
                 .expect("Failed creating fragment shader");
 
-            let header: &str = if context.version().is_embedded {
-                "#version 300 es
-                    #ifdef GL_FRAGMENT_PRECISION_HIGH
-                        precision highp float;
-                        precision highp int;
-                        precision highp sampler2DArray;
-                        precision highp sampler3D;
-                    #else
-                        precision mediump float;
-                        precision mediump int;
-                        precision mediump sampler2DArray;
-                        precision mediump sampler3D;
-                    #endif\n"
-            } else {
-                "#version 330 core\n"
-            };
-            let vertex_shader_source = format!("{}{}", header, vertex_shader_source);
-            let fragment_shader_source = format!("{}{}", header, fragment_shader_source);
-
             context.shader_source(vert_shader, &vertex_shader_source);
             context.shader_source(frag_shader, &fragment_shader_source);
             context.compile_shader(vert_shader);
@@ -61,6 +62,10 @@ impl Program {
             let id = context.create_program().expect("Failed creating program");
             context.attach_shader(id, vert_shader);
             context.attach_shader(id, frag_shader);
+            #[cfg(not(target_arch = "wasm32"))]
+            if binary_path.is_some() {
+                context.program_binary_retrievable_hint(id, true);
+            }
             context.link_program(id);
 
             if !context.get_program_link_status(id) {
@@ -92,43 +97,55 @@ impl Program {
             context.delete_shader(vert_shader);
             context.delete_shader(frag_shader);
 
-            // Init vertex attributes
-            let num_attribs = context.get_active_attributes(id);
-            let mut attributes = HashMap::new();
-            for i in 0..num_attribs {
-                if let Some(crate::context::ActiveAttribute { name, .. }) = context
-                    .get_active_attribute(id, i)
-                    .filter(|a| !a.name.starts_with("gl_"))
-                {
-                    if let Some(location) = context.get_attrib_location(id, &name) {
-                        attributes.insert(name, location);
-                    }
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(path) = binary_path {
+                store_program_binary(context, id, &path);
+            }
+
+            Ok(Self::from_linked_program(context, id))
+        }
+    }
+
+    ///
+    /// Creates a program from an already linked low-level program by looking up the active attributes and uniforms.
+    ///
+    unsafe fn from_linked_program(context: &Context, id: crate::context::Program) -> Self {
+        // Init vertex attributes
+        let num_attribs = context.get_active_attributes(id);
+        let mut attributes = HashMap::new();
+        for i in 0..num_attribs {
+            if let Some(crate::context::ActiveAttribute { name, .. }) = context
+                .get_active_attribute(id, i)
+                .filter(|a| !a.name.starts_with("gl_"))
+            {
+                if let Some(location) = context.get_attrib_location(id, &name) {
+                    attributes.insert(name, location);
                 }
             }
+        }
 
-            // Init uniforms
-            let num_uniforms = context.get_active_uniforms(id);
-            let mut uniforms = HashMap::new();
-            for i in 0..num_uniforms {
-                if let Some(crate::context::ActiveUniform { name, .. }) = context
-                    .get_active_uniform(id, i)
-                    .filter(|a| !a.name.starts_with("gl_"))
-                {
-                    if let Some(location) = context.get_uniform_location(id, &name) {
-                        let name = name.split('[').next().unwrap().to_string();
-                        uniforms.insert(name, location);
-                    }
+        // Init uniforms
+        let num_uniforms = context.get_active_uniforms(id);
+        let mut uniforms = HashMap::new();
+        for i in 0..num_uniforms {
+            if let Some(crate::context::ActiveUniform { name, .. }) = context
+                .get_active_uniform(id, i)
+                .filter(|a| !a.name.starts_with("gl_"))
+            {
+                if let Some(location) = context.get_uniform_location(id, &name) {
+                    let name = name.split('[').next().unwrap().to_string();
+                    uniforms.insert(name, location);
                 }
             }
+        }
 
-            Ok(Program {
-                context: context.clone(),
-                id,
-                attributes,
-                uniforms,
-                uniform_blocks: RwLock::new(HashMap::new()),
-                textures: RwLock::new(HashMap::new()),
-            })
+        Program {
+            context: context.clone(),
+            id,
+            attributes,
+            uniforms,
+            uniform_blocks: RwLock::new(HashMap::new()),
+            textures: RwLock::new(HashMap::new()),
         }
     }
 
@@ -322,6 +339,30 @@ impl Program {
         }
     }
 
+    ///
+    /// Sends the given data to a uniform buffer which is shared between all programs created with the same context and identified by the given name,
+    /// and uses it in this shader program for the uniform block with the same name (see [Self::use_uniform_block]).
+    /// The data is only sent to the GPU when it is different from the data used last time, which makes this efficient for data that is the same for many draw calls,
+    /// for example camera and light data. The uniform block must be declared with the std140 layout and the data must follow that layout.
+    ///
+    /// # Panic
+    /// Will panic if the uniform block is not defined in the shader code or not used.
+    ///
+    pub fn use_shared_uniform_block(&self, name: &str, data: &[f32]) {
+        let mut buffers = self.context.shared_uniform_buffers.write().unwrap();
+        if !matches!(buffers.get(name).and_then(|b| b.get(0)), Some(d) if d.len() == data.len()) {
+            buffers.insert(
+                name.to_owned(),
+                UniformBuffer::new(&self.context, &[data.len() as u32]),
+            );
+        }
+        let buffer = buffers.get_mut(name).unwrap();
+        if buffer.get(0) != Some(data) {
+            buffer.update(0, data);
+        }
+        self.use_uniform_block(name, buffer);
+    }
+
     ///
     /// Uses the given [VertexBuffer] data in this shader program and associates it with the given named variable.
     /// Each value in the buffer is used when rendering one vertex using the [Program::draw_arrays] or [Program::draw_elements] methods.
@@ -613,6 +654,18 @@ impl Program {
         self.uniforms.contains_key(name)
     }
 
+    ///
+    /// Returns true if this program uses the uniform block with the given name.
+    ///
+    pub fn requires_uniform_block(&self, name: &str) -> bool {
+        self.uniform_blocks.read().unwrap().contains_key(name)
+            || unsafe {
+                self.context
+                    .get_uniform_block_index(self.id, name)
+                    .is_some()
+            }
+    }
+
     ///
     /// Returns true if this program uses the attribute with the given name.
     ///
@@ -650,3 +703,114 @@ impl Drop for Program {
         }
     }
 }
+
+fn shader_header(context: &Context) -> &'static str {
+    if context.version().is_embedded {
+        "#version 300 es
+            #ifdef GL_FRAGMENT_PRECISION_HIGH
+                precision highp float;
+                precision highp int;
+                precision highp sampler2DArray;
+                precision highp sampler3D;
+            #else
+                precision mediump float;
+                precision mediump int;
+                precision mediump sampler2DArray;
+                precision mediump sampler3D;
+            #endif\n"
+    } else {
+        "#version 330 core\n"
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn program_binary_file_name(
+    context: &Context,
+    vertex_shader_source: &str,
+    fragment_shader_source: &str,
+) -> String {
+    // FNV-1a is used since the hash has to be stable between runs and compiler versions
+    let mut hash = 0xcbf29ce484222325u64;
+    let driver = unsafe {
+        [
+            context.get_parameter_string(crate::context::VENDOR),
+            context.get_parameter_string(crate::context::RENDERER),
+            context.get_parameter_string(crate::context::VERSION),
+        ]
+    };
+    for part in driver
+        .iter()
+        .map(|s| s.as_str())
+        .chain([vertex_shader_source, fragment_shader_source])
+    {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}.bin", hash)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe fn load_program_binary(
+    context: &Context,
+    path: &std::path::Path,
+) -> Option<crate::context::Program> {
+    let data = std::fs::read(path).ok()?;
+    if data.len() < 4 {
+        return None;
+    }
+    let binary = crate::context::ProgramBinary {
+        format: u32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+        buffer: data[4..].to_vec(),
+    };
+    let id = context.create_program().expect("Failed creating program");
+    context.program_binary(id, &binary);
+    if context.get_program_link_status(id) {
+        Some(id)
+    } else {
+        // The binary is rejected, for example because the driver has been updated, so it is compiled and stored again
+        context.delete_program(id);
+        std::fs::remove_file(path).ok();
+        None
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe fn store_program_binary(
+    context: &Context,
+    id: crate::context::Program,
+    path: &std::path::Path,
+) {
+    if let Some(binary) = context.get_program_binary(id) {
+        let mut data = binary.format.to_le_bytes().to_vec();
+        data.extend(binary.buffer);
+        // Write to a temporary file first, so another process never reads a partially written binary.
+        // Failing to store the binary only means that the program is compiled again next time
+        let temporary_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        if std::fs::write(&temporary_path, data).is_err()
+            || std::fs::rename(&temporary_path, path).is_err()
+        {
+            std::fs::remove_file(&temporary_path).ok();
+        }
+    }
+}
+
+///
+/// Returns whether or not program binaries can be retrieved and loaded, ie. if the context is OpenGL 4.1 or OpenGL ES 3.0 or newer
+/// or supports the `GL_ARB_get_program_binary` extension, and the driver supports at least one program binary format.
+///
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn is_program_binary_supported(context: &Context) -> bool {
+    let version = context.version();
+    let core_support = if version.is_embedded {
+        version.major >= 3
+    } else {
+        (version.major, version.minor) >= (4, 1)
+    };
+    let extension_support = context
+        .supported_extensions()
+        .contains("GL_ARB_get_program_binary");
+    (core_support || extension_support)
+        && unsafe { context.get_parameter_i32(crate::context::NUM_PROGRAM_BINARY_FORMATS) } > 0
+}
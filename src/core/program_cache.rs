@@ -0,0 +1,245 @@
+use crate::core::*;
+#[cfg(target_arch = "wasm32")]
+use instant::Instant;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+///
+/// Statistics about the usage of a [ProgramCache].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProgramCacheStatistics {
+    /// The number of times a requested program was found in the cache.
+    pub hits: u64,
+    /// The number of times a requested program was not found in the cache and therefore had to be compiled.
+    pub misses: u64,
+    /// The number of programs that has been evicted from the cache because the capacity was exceeded.
+    pub evictions: u64,
+    /// The total time spent compiling programs in seconds.
+    pub compile_time: f64,
+}
+
+///
+/// A cache of [Program]s identified by a byte key, for example the concatenated ids of a geometry, a material and a set of lights.
+/// If a capacity is specified, the least recently used programs are evicted from the cache when the capacity is exceeded.
+/// Note that if the capacity is less than the number of programs used each frame, programs are recompiled every frame.
+///
+/// The cache also remembers the ids of programs that failed to compile, see [Self::insert_failure], to avoid recompiling a broken program every frame.
+///
+#[derive(Default)]
+pub struct ProgramCache {
+    programs: HashMap<Vec<u8>, (Program, u64)>,
+    failures: HashMap<Vec<u8>, String>,
+    error_fallback: bool,
+    capacity: Option<usize>,
+    counter: u64,
+    statistics: ProgramCacheStatistics,
+}
+
+impl ProgramCache {
+    ///
+    /// Creates a new empty program cache without a capacity.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns the maximum number of programs in the cache or `None` if the cache is unbounded.
+    ///
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    ///
+    /// Sets the maximum number of programs in the cache, use `None` for an unbounded cache.
+    /// If the cache contains more programs than the new capacity, the least recently used programs are evicted.
+    ///
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        if let Some(capacity) = capacity {
+            while self.programs.len() > capacity {
+                self.evict_least_recently_used();
+            }
+        }
+    }
+
+    ///
+    /// Returns the number of programs in the cache.
+    ///
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    ///
+    /// Returns whether or not the cache is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    ///
+    /// Returns whether or not a program with the given id is in the cache.
+    /// This is not counted as a hit or miss in the [ProgramCacheStatistics].
+    ///
+    pub fn contains_key(&self, id: &[u8]) -> bool {
+        self.programs.contains_key(id)
+    }
+
+    ///
+    /// Returns the program with the given id if it is in the cache.
+    /// Opposite to [Self::get], this is not counted as a hit or miss in the [ProgramCacheStatistics] and the program is not marked as used.
+    ///
+    pub fn peek(&self, id: &[u8]) -> Option<&Program> {
+        self.programs.get(id).map(|(program, _)| program)
+    }
+
+    ///
+    /// Returns the program with the given id if it is in the cache and marks it as the most recently used program.
+    ///
+    pub fn get(&mut self, id: &[u8]) -> Option<&Program> {
+        self.counter += 1;
+        if let Some((program, last_used)) = self.programs.get_mut(id) {
+            self.statistics.hits += 1;
+            *last_used = self.counter;
+            Some(program)
+        } else {
+            self.statistics.misses += 1;
+            None
+        }
+    }
+
+    ///
+    /// Returns the program with the given id if it is in the cache, otherwise the program is created using the given callback and inserted into the cache.
+    /// The time spent in the callback is added to the compile time in the [ProgramCacheStatistics].
+    ///
+    pub fn get_or_insert_with(
+        &mut self,
+        id: Vec<u8>,
+        create: impl FnOnce() -> Program,
+    ) -> &Program {
+        match self.try_get_or_insert_with::<()>(id, || Ok(create())) {
+            Ok(program) => program,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    ///
+    /// Same as [Self::get_or_insert_with] except that the callback can fail, in which case nothing is inserted and the error is returned.
+    ///
+    pub fn try_get_or_insert_with<E>(
+        &mut self,
+        id: Vec<u8>,
+        create: impl FnOnce() -> Result<Program, E>,
+    ) -> Result<&Program, E> {
+        self.counter += 1;
+        if self.programs.contains_key(&id) {
+            self.statistics.hits += 1;
+        } else {
+            self.statistics.misses += 1;
+            let start = Instant::now();
+            let program = create();
+            self.statistics.compile_time += start.elapsed().as_secs_f64();
+            self.insert(id.clone(), program?);
+        }
+        let (program, last_used) = self.programs.get_mut(&id).unwrap();
+        *last_used = self.counter;
+        Ok(program)
+    }
+
+    ///
+    /// Inserts the given program into the cache, replacing any program with the same id.
+    /// If the capacity is exceeded, the least recently used programs are evicted.
+    ///
+    pub fn insert(&mut self, id: Vec<u8>, program: Program) {
+        self.counter += 1;
+        self.programs.insert(id, (program, self.counter));
+        if let Some(capacity) = self.capacity {
+            while self.programs.len() > capacity.max(1) {
+                self.evict_least_recently_used();
+            }
+        }
+    }
+
+    ///
+    /// Removes the program with the given id from the cache and returns it if it was in the cache.
+    /// Any failure registered for the id is also removed, so the program is compiled again the next time it is requested.
+    ///
+    pub fn remove(&mut self, id: &[u8]) -> Option<Program> {
+        self.failures.remove(id);
+        self.programs.remove(id).map(|(program, _)| program)
+    }
+
+    ///
+    /// Removes all programs and registered failures for which the given predicate returns false when called with the id.
+    ///
+    pub fn retain(&mut self, mut keep: impl FnMut(&[u8]) -> bool) {
+        self.programs.retain(|id, _| keep(id));
+        self.failures.retain(|id, _| keep(id));
+    }
+
+    ///
+    /// Removes all programs and registered failures from the cache. The statistics are not reset, use [Self::reset_statistics] for that.
+    ///
+    pub fn clear(&mut self) {
+        self.programs.clear();
+        self.failures.clear();
+    }
+
+    ///
+    /// Registers that the program with the given id failed to compile together with a message describing the failure.
+    /// The failure is kept until the id is removed using [Self::remove] or [Self::clear].
+    ///
+    pub fn insert_failure(&mut self, id: Vec<u8>, message: String) {
+        self.failures.insert(id, message);
+    }
+
+    ///
+    /// Returns the message describing why the program with the given id failed to compile, if a failure is registered for the id.
+    ///
+    pub fn failure(&self, id: &[u8]) -> Option<&str> {
+        self.failures.get(id).map(|message| message.as_str())
+    }
+
+    ///
+    /// Returns whether or not the renderer falls back to rendering with a magenta error material when a program fails to compile, see [Self::set_error_fallback].
+    ///
+    pub fn error_fallback(&self) -> bool {
+        self.error_fallback
+    }
+
+    ///
+    /// Sets whether or not the renderer should fall back to rendering with a magenta error material instead of panicking when a program fails to compile.
+    /// This is disabled by default. Use for example [RenderTarget::try_render](crate::renderer::RenderTarget::try_render) to get the compilation error.
+    ///
+    pub fn set_error_fallback(&mut self, error_fallback: bool) {
+        self.error_fallback = error_fallback;
+    }
+
+    ///
+    /// Returns the statistics about the usage of this cache since it was created or since [Self::reset_statistics] was called.
+    ///
+    pub fn statistics(&self) -> ProgramCacheStatistics {
+        self.statistics
+    }
+
+    ///
+    /// Resets the statistics about the usage of this cache.
+    ///
+    pub fn reset_statistics(&mut self) {
+        self.statistics = ProgramCacheStatistics::default();
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some(id) = self
+            .programs
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(id, _)| id.clone())
+        {
+            self.programs.remove(&id);
+            self.statistics.evictions += 1;
+        }
+    }
+}
@@ -31,6 +31,15 @@ pub struct RenderStates {
     /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped in a render call.
     ///
     pub cull: Cull,
+
+    ///
+    /// Defines the stencil test in a render call.
+    /// The stencil test determines whether or not a fragment from the current render call should be discarded
+    /// when comparing a reference value with the value in the stencil buffer and how the stencil buffer is updated.
+    ///
+    /// **Note:** The stencil test has no effect if the render call is not writing to a target with a stencil buffer.
+    ///
+    pub stencil_test: StencilTest,
 }
 
 ///
@@ -240,3 +249,122 @@ pub enum BlendEquationType {
     Max,
     Min,
 }
+
+///
+/// Defines the stencil test in a render call, see [RenderStates::stencil_test].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilTest {
+    /// The stencil test is disabled and the stencil buffer is not modified.
+    Disabled,
+    /// The stencil test is enabled.
+    Enabled {
+        /// The function used to compare the reference value with the value in the stencil buffer, ie. the fragment passes if `reference <function> stencil` is true.
+        function: StencilFunction,
+        /// The reference value.
+        reference: u8,
+        /// A mask that is applied to both the reference value and the value in the stencil buffer before comparing.
+        read_mask: u8,
+        /// A mask that determines which bits of the stencil buffer are updated.
+        write_mask: u8,
+        /// The operation to perform when the stencil test fails.
+        stencil_fail: StencilOp,
+        /// The operation to perform when the stencil test passes but the depth test fails.
+        depth_fail: StencilOp,
+        /// The operation to perform when both the stencil and depth test passes.
+        pass: StencilOp,
+    },
+}
+
+impl StencilTest {
+    ///
+    /// Always passes and writes the given reference value to the stencil buffer where the depth test passes.
+    /// This is usually used to write a mask into the stencil buffer.
+    ///
+    pub const fn write(reference: u8) -> Self {
+        Self::Enabled {
+            function: StencilFunction::Always,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0xFF,
+            stencil_fail: StencilOp::Keep,
+            depth_fail: StencilOp::Keep,
+            pass: StencilOp::Replace,
+        }
+    }
+
+    ///
+    /// Only passes where the value in the stencil buffer is equal to the given reference value and does not modify the stencil buffer.
+    ///
+    pub const fn equal(reference: u8) -> Self {
+        Self::Enabled {
+            function: StencilFunction::Equal,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0x00,
+            stencil_fail: StencilOp::Keep,
+            depth_fail: StencilOp::Keep,
+            pass: StencilOp::Keep,
+        }
+    }
+
+    ///
+    /// Only passes where the value in the stencil buffer is not equal to the given reference value and does not modify the stencil buffer.
+    ///
+    pub const fn not_equal(reference: u8) -> Self {
+        Self::Enabled {
+            function: StencilFunction::NotEqual,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0x00,
+            stencil_fail: StencilOp::Keep,
+            depth_fail: StencilOp::Keep,
+            pass: StencilOp::Keep,
+        }
+    }
+}
+
+impl Default for StencilTest {
+    fn default() -> Self {
+        Self::Disabled
+    }
+}
+
+///
+/// The function used to compare the reference value with the value in the stencil buffer in [StencilTest].
+///
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilFunction {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+///
+/// The operation to perform on the value in the stencil buffer in [StencilTest].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilOp {
+    /// Keeps the current value.
+    Keep,
+    /// Sets the value to zero.
+    Zero,
+    /// Sets the value to the reference value.
+    Replace,
+    /// Increments the value, clamping to the maximum value.
+    Increment,
+    /// Increments the value, wrapping to zero when exceeding the maximum value.
+    IncrementWrap,
+    /// Decrements the value, clamping to zero.
+    Decrement,
+    /// Decrements the value, wrapping to the maximum value when going below zero.
+    DecrementWrap,
+    /// Inverts the bits of the value.
+    Invert,
+}
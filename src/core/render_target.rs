@@ -99,7 +99,17 @@ impl<'a> RenderTarget<'a> {
     pub fn clear_partially(&self, scissor_box: ScissorBox, clear_state: ClearState) -> &Self {
         self.context.set_scissor(scissor_box);
         self.bind(crate::context::DRAW_FRAMEBUFFER);
-        clear_state.apply(&self.context);
+        match self.color.as_ref().map(|color| color.sampler_type()) {
+            Some(sampler_type) if sampler_type != SamplerType::Float => {
+                // Integer color buffers cannot be cleared with glClear
+                clear_state.apply_integer(
+                    &self.context,
+                    sampler_type,
+                    self.color.as_ref().unwrap().draw_buffer_count(),
+                );
+            }
+            _ => clear_state.apply(&self.context),
+        }
         self
     }
 
@@ -141,6 +151,7 @@ impl<'a> RenderTarget<'a> {
     /// The generic parameter `T` is limited to:
     /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any render target using `u8` as its base type.
     /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any render target using `f16` or `f32` as its base type.
+    /// - 32-bit integer RGBA (Specify `T` as either `Vec4<u32>`, `[u32; 4]`, `Vec4<i32>` or `[i32; 4]`) which works with any render target using an unsigned or signed integer base type respectively.
     ///
     pub fn read_color<T: TextureDataType>(&self) -> Vec<T> {
         self.read_color_partially(self.scissor_box())
@@ -157,6 +168,7 @@ impl<'a> RenderTarget<'a> {
     /// The generic parameter `T` is limited to:
     /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any render target using `u8` as its base type.
     /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any render target using `f16` or `f32` as its base type.
+    /// - 32-bit integer RGBA (Specify `T` as either `Vec4<u32>`, `[u32; 4]`, `Vec4<i32>` or `[i32; 4]`) which works with any render target using an unsigned or signed integer base type respectively.
     ///
     pub fn read_color_partially<T: TextureDataType>(&self, scissor_box: ScissorBox) -> Vec<T> {
         if self.id.is_some() && self.color.is_none() {
@@ -167,10 +179,12 @@ impl<'a> RenderTarget<'a> {
 
         // On web, the read format needs to be RGBA and f16 is not supported (see https://webglfundamentals.org/webgl/lessons/webgl-readpixels.html).
         #[cfg(target_arch = "wasm32")]
-        if format != crate::context::RGBA
-            || !(data_type == crate::context::UNSIGNED_BYTE || data_type == crate::context::FLOAT)
+        if !(format == crate::context::RGBA
+            && (data_type == crate::context::UNSIGNED_BYTE || data_type == crate::context::FLOAT))
+            && !(format == crate::context::RGBA_INTEGER
+                && (data_type == crate::context::UNSIGNED_INT || data_type == crate::context::INT))
         {
-            panic!("Only the texture data types `Vec4<T>` and `[T; 4]` where `T` is either `u8` or `f32` are supported when reading color from a render target on web.");
+            panic!("Only the texture data types `Vec4<T>` and `[T; 4]` where `T` is either `u8`, `u32`, `i32` or `f32` are supported when reading color from a render target on web.");
         }
 
         self.bind(crate::context::DRAW_FRAMEBUFFER);
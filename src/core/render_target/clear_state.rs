@@ -1,8 +1,9 @@
 use crate::core::*;
 
 ///
-/// Defines which channels (red, green, blue, alpha and depth) to clear when starting to write to a [RenderTarget].
+/// Defines which channels (red, green, blue, alpha, depth and stencil) to clear when starting to write to a [RenderTarget].
 /// If `None` then the channel is not cleared and if `Some(value)` the channel is cleared to that value (the value must be between 0 and 1).
+/// When clearing a color target with an integer base type (see [SamplerType]), the color values are converted to integers instead.
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct ClearState {
@@ -16,6 +17,8 @@ pub struct ClearState {
     pub alpha: Option<f32>,
     /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane.
     pub depth: Option<f32>,
+    /// Defines the clear value for the stencil channel. Only has an effect if the target has a stencil buffer.
+    pub stencil: Option<u8>,
 }
 
 impl ClearState {
@@ -29,6 +32,7 @@ impl ClearState {
             blue: None,
             alpha: None,
             depth: None,
+            stencil: None,
         }
     }
 
@@ -42,6 +46,7 @@ impl ClearState {
             blue: None,
             alpha: None,
             depth: Some(depth),
+            stencil: None,
         }
     }
 
@@ -55,6 +60,7 @@ impl ClearState {
             blue: Some(blue),
             alpha: Some(alpha),
             depth: None,
+            stencil: None,
         }
     }
 
@@ -68,6 +74,42 @@ impl ClearState {
             blue: Some(blue),
             alpha: Some(alpha),
             depth: Some(depth),
+            stencil: None,
+        }
+    }
+
+    ///
+    /// The stencil will be cleared to the given value.
+    ///
+    pub const fn stencil(stencil: u8) -> Self {
+        Self {
+            red: None,
+            green: None,
+            blue: None,
+            alpha: None,
+            depth: None,
+            stencil: Some(stencil),
+        }
+    }
+
+    ///
+    /// The color channels (red, green, blue and alpha), depth and stencil will be cleared to the given values.
+    ///
+    pub const fn color_depth_and_stencil(
+        red: f32,
+        green: f32,
+        blue: f32,
+        alpha: f32,
+        depth: f32,
+        stencil: u8,
+    ) -> Self {
+        Self {
+            red: Some(red),
+            green: Some(green),
+            blue: Some(blue),
+            alpha: Some(alpha),
+            depth: Some(depth),
+            stencil: Some(stencil),
         }
     }
 
@@ -92,16 +134,79 @@ impl ClearState {
                     self.alpha.unwrap_or(1.0),
                 );
             }
+            let mut mask = 0;
+            if clear_color {
+                mask |= crate::context::COLOR_BUFFER_BIT;
+            }
             if let Some(depth) = self.depth {
                 context.clear_depth_f32(depth);
+                mask |= crate::context::DEPTH_BUFFER_BIT;
+            }
+            self.apply_stencil(context, &mut mask);
+            if mask != 0 {
+                context.clear(mask);
+            }
+        }
+    }
+
+    unsafe fn apply_stencil(&self, context: &Context, mask: &mut u32) {
+        if let Some(stencil) = self.stencil {
+            context.stencil_mask(0xFF);
+            context.clear_stencil(stencil as i32);
+            *mask |= crate::context::STENCIL_BUFFER_BIT;
+        }
+    }
+
+    pub(in crate::core) fn apply_integer(
+        &self,
+        context: &Context,
+        sampler_type: SamplerType,
+        draw_buffer_count: u32,
+    ) {
+        context.set_write_mask(WriteMask {
+            red: self.red.is_some(),
+            green: self.green.is_some(),
+            blue: self.blue.is_some(),
+            alpha: self.alpha.is_some(),
+            depth: self.depth.is_some(),
+        });
+        let color = [
+            self.red.unwrap_or(0.0),
+            self.green.unwrap_or(0.0),
+            self.blue.unwrap_or(0.0),
+            self.alpha.unwrap_or(1.0),
+        ];
+        unsafe {
+            if self.red.is_some()
+                || self.green.is_some()
+                || self.blue.is_some()
+                || self.alpha.is_some()
+            {
+                for draw_buffer in 0..draw_buffer_count {
+                    if sampler_type == SamplerType::UnsignedInteger {
+                        context.clear_buffer_u32_slice(
+                            crate::context::COLOR,
+                            draw_buffer,
+                            &color.map(|c| c as u32),
+                        );
+                    } else {
+                        context.clear_buffer_i32_slice(
+                            crate::context::COLOR,
+                            draw_buffer,
+                            &color.map(|c| c as i32),
+                        );
+                    }
+                }
+            }
+            let mut mask = 0;
+            if let Some(depth) = self.depth {
+                context.clear_depth_f32(depth);
+                mask |= crate::context::DEPTH_BUFFER_BIT;
+            }
+            self.apply_stencil(context, &mut mask);
+            if mask != 0 {
+                context.clear(mask);
             }
-            context.clear(if clear_color && self.depth.is_some() {
-                crate::context::COLOR_BUFFER_BIT | crate::context::DEPTH_BUFFER_BIT
-            } else if clear_color {
-                crate::context::COLOR_BUFFER_BIT
-            } else {
-                crate::context::DEPTH_BUFFER_BIT
-            });
         }
     }
 }
@@ -85,6 +85,7 @@ impl<'a> ColorTarget<'a> {
             scissor_box,
             ClearState {
                 depth: None,
+                stencil: None,
                 ..clear_state
             },
         );
@@ -125,6 +126,7 @@ impl<'a> ColorTarget<'a> {
     /// The generic parameter `T` is limited to:
     /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any color target using `u8` as its base type.
     /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any color target using `f16` or `f32` as its base type.
+    /// - 32-bit integer RGBA (Specify `T` as either `Vec4<u32>`, `[u32; 4]`, `Vec4<i32>` or `[i32; 4]`) which works with any color target using an unsigned or signed integer base type respectively.
     ///
     pub fn read<T: TextureDataType>(&self) -> Vec<T> {
         self.read_partially(self.scissor_box())
@@ -141,6 +143,7 @@ impl<'a> ColorTarget<'a> {
     /// The generic parameter `T` is limited to:
     /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any color target using `u8` as its base type.
     /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any color target using `f16` or `f32` as its base type.
+    /// - 32-bit integer RGBA (Specify `T` as either `Vec4<u32>`, `[u32; 4]`, `Vec4<i32>` or `[i32; 4]`) which works with any color target using an unsigned or signed integer base type respectively.
     ///
     pub fn read_partially<T: TextureDataType>(&self, scissor_box: ScissorBox) -> Vec<T> {
         self.as_render_target().read_color_partially(scissor_box)
@@ -186,6 +189,20 @@ impl<'a> ColorTarget<'a> {
         }
     }
 
+    pub(super) fn sampler_type(&self) -> SamplerType {
+        self.target
+            .map(|target| target.sampler_type())
+            .unwrap_or(SamplerType::Float)
+    }
+
+    pub(super) fn draw_buffer_count(&self) -> u32 {
+        match self.target {
+            Some(ColorTexture::Array { layers, .. }) => layers.len() as u32,
+            Some(ColorTexture::CubeMap { sides, .. }) => sides.len() as u32,
+            _ => 1,
+        }
+    }
+
     pub(super) fn as_render_target(&self) -> RenderTarget<'a> {
         RenderTarget::new_color(self.clone())
     }
@@ -45,6 +45,11 @@ pub use three_d_asset::texture::{
 /// The basic data type used for each channel of each pixel in a texture.
 pub trait TextureDataType: DataType {}
 impl TextureDataType for u8 {}
+impl TextureDataType for u16 {}
+impl TextureDataType for u32 {}
+impl TextureDataType for i8 {}
+impl TextureDataType for i16 {}
+impl TextureDataType for i32 {}
 impl TextureDataType for f16 {}
 impl TextureDataType for f32 {}
 
@@ -59,6 +64,55 @@ impl TextureDataType for Quat {}
 
 impl<T: TextureDataType + ?Sized> TextureDataType for &T {}
 
+///
+/// The type of sampler needed to sample a color texture in a shader which is determined by the [TextureDataType] of the texture.
+/// Textures with `u16`, `u32`, `i8`, `i16` or `i32` as base type are integer textures which are always sampled with nearest interpolation and without mip maps.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplerType {
+    /// A `sampler` returning normalized or floating point values (`vec4`), used for `u8`, `f16` and `f32` textures.
+    Float,
+    /// A `usampler` returning unsigned integer values (`uvec4`), used for `u16` and `u32` textures.
+    UnsignedInteger,
+    /// An `isampler` returning signed integer values (`ivec4`), used for `i8`, `i16` and `i32` textures.
+    SignedInteger,
+}
+
+impl SamplerType {
+    pub(in crate::core) fn from_data_type<T: TextureDataType>() -> Self {
+        match T::data_type() {
+            crate::context::UNSIGNED_SHORT | crate::context::UNSIGNED_INT => Self::UnsignedInteger,
+            crate::context::BYTE | crate::context::SHORT | crate::context::INT => {
+                Self::SignedInteger
+            }
+            _ => Self::Float,
+        }
+    }
+
+    ///
+    /// Returns the GLSL prefix for samplers and vectors of this type, ie. `""`, `"u"` or `"i"`.
+    ///
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::Float => "",
+            Self::UnsignedInteger => "u",
+            Self::SignedInteger => "i",
+        }
+    }
+
+    ///
+    /// Returns the bits added to the id of a color texture with this sampler type, see [ColorTexture::id].
+    /// The bits are not used by the rest of the color texture id, the depth texture id (see [DepthTexture::id]) or the ids of the effects combining them.
+    ///
+    fn id(&self) -> u16 {
+        match self {
+            Self::Float => 0,
+            Self::UnsignedInteger => 0b1u16 << 5,
+            Self::SignedInteger => 0b1u16 << 10,
+        }
+    }
+}
+
 /// The basic data type used for each pixel in a depth texture.
 pub trait DepthTextureDataType: DepthDataType {}
 
@@ -67,9 +121,27 @@ pub trait DepthTextureDataType: DepthDataType {}
 #[derive(Clone, Copy, Default, Debug)]
 pub struct f24 {}
 
+///
+/// 24 bit float depth combined with an 8 bit stencil which can be used as [DepthTextureDataType].
+/// Depth textures with this data type have a stencil buffer and can therefore be used together with [StencilTest].
+///
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct f24s8 {}
+
+///
+/// 32 bit float depth combined with an 8 bit stencil which can be used as [DepthTextureDataType].
+/// Depth textures with this data type have a stencil buffer and can therefore be used together with [StencilTest].
+///
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct f32s8 {}
+
 impl DepthTextureDataType for f16 {}
 impl DepthTextureDataType for f24 {}
 impl DepthTextureDataType for f32 {}
+impl DepthTextureDataType for f24s8 {}
+impl DepthTextureDataType for f32s8 {}
 
 ///
 /// A reference to some type of texture containing colors.
@@ -114,30 +186,45 @@ impl ColorTexture<'_> {
         }
     }
 
+    ///
+    /// Returns the type of sampler needed to sample this texture in a shader.
+    ///
+    pub fn sampler_type(&self) -> SamplerType {
+        match self {
+            ColorTexture::Single(texture) => texture.sampler_type(),
+            ColorTexture::Array { texture, .. } => texture.sampler_type(),
+            ColorTexture::CubeMap { .. } => SamplerType::Float,
+        }
+    }
+
     ///
     /// Returns the fragment shader source for using this texture in a shader.
+    /// For integer textures (see [SamplerType]), the sample functions return `uvec4` or `ivec4` instead of `vec4`.
     ///
     pub fn fragment_shader_source(&self) -> String {
+        let p = self.sampler_type().prefix();
         match self {
-            Self::Single(_) => "
-                uniform sampler2D colorMap;
-                vec4 sample_color(vec2 uv)
-                {
+            Self::Single(_) => format!(
+                "
+                uniform {p}sampler2D colorMap;
+                {p}vec4 sample_color(vec2 uv)
+                {{
                     return texture(colorMap, uv);
-                }"
-            .to_owned(),
-            Self::Array { .. } => "
-                uniform sampler2DArray colorMap;
+                }}"
+            ),
+            Self::Array { .. } => format!(
+                "
+                uniform {p}sampler2DArray colorMap;
                 uniform int colorLayers[4];
-                vec4 sample_color(vec2 uv)
-                {
+                {p}vec4 sample_color(vec2 uv)
+                {{
                     return texture(colorMap, vec3(uv, colorLayers[0]));
-                }
-                vec4 sample_layer(vec2 uv, int index)
-                {
+                }}
+                {p}vec4 sample_layer(vec2 uv, int index)
+                {{
                     return texture(colorMap, vec3(uv, colorLayers[index]));
-                }"
-            .to_owned(),
+                }}"
+            ),
             Self::CubeMap { .. } => todo!(),
         }
     }
@@ -147,8 +234,8 @@ impl ColorTexture<'_> {
     ///
     pub fn id(&self) -> u16 {
         match self {
-            Self::Single { .. } => 1u16 << 3,
-            Self::Array { .. } => 10u16 << 3,
+            Self::Single { .. } => 1u16 << 3 | self.sampler_type().id(),
+            Self::Array { .. } => 10u16 << 3 | self.sampler_type().id(),
             Self::CubeMap { .. } => {
                 todo!()
             }
@@ -362,6 +449,11 @@ fn calculate_number_of_mip_maps<T: TextureDataType>(
         return 1;
     }
 
+    // Integer textures cannot be filtered
+    if is_integer_data_type::<T>() {
+        return 1;
+    }
+
     if mip_map_filter.is_some() {
         let max_size = width.max(height).max(depth.unwrap_or(0));
         let power_of_two = max_size.next_power_of_two();
@@ -371,6 +463,17 @@ fn calculate_number_of_mip_maps<T: TextureDataType>(
     }
 }
 
+///
+/// Returns the interpolation to use for the given texture data type, integer textures are always sampled with nearest interpolation.
+///
+fn interpolation_for<T: TextureDataType>(interpolation: Interpolation) -> Interpolation {
+    if is_integer_data_type::<T>() {
+        Interpolation::Nearest
+    } else {
+        interpolation
+    }
+}
+
 fn wrapping_from(wrapping: Wrapping) -> i32 {
     (match wrapping {
         Wrapping::Repeat => crate::context::REPEAT,
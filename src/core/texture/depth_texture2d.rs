@@ -8,6 +8,7 @@ pub struct DepthTexture2D {
     id: crate::context::Texture,
     width: u32,
     height: u32,
+    has_stencil: bool,
 }
 
 impl DepthTexture2D {
@@ -27,6 +28,7 @@ impl DepthTexture2D {
             id,
             width,
             height,
+            has_stencil: T::has_stencil(),
         };
         texture.bind();
         set_parameters(
@@ -73,7 +75,11 @@ impl DepthTexture2D {
         unsafe {
             self.context.framebuffer_texture_2d(
                 crate::context::FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                if self.has_stencil {
+                    crate::context::DEPTH_STENCIL_ATTACHMENT
+                } else {
+                    crate::context::DEPTH_ATTACHMENT
+                },
                 crate::context::TEXTURE_2D,
                 Some(self.id),
                 0,
@@ -9,6 +9,7 @@ pub struct DepthTexture2DArray {
     width: u32,
     height: u32,
     depth: u32,
+    has_stencil: bool,
 }
 
 impl DepthTexture2DArray {
@@ -30,6 +31,7 @@ impl DepthTexture2DArray {
             width,
             height,
             depth,
+            has_stencil: T::has_stencil(),
         };
         texture.bind();
         set_parameters(
@@ -82,7 +84,11 @@ impl DepthTexture2DArray {
         unsafe {
             self.context.framebuffer_texture_layer(
                 crate::context::DRAW_FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                if self.has_stencil {
+                    crate::context::DEPTH_STENCIL_ATTACHMENT
+                } else {
+                    crate::context::DEPTH_ATTACHMENT
+                },
                 Some(self.id),
                 0,
                 layer as i32,
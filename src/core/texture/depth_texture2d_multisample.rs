@@ -6,6 +6,7 @@ pub struct DepthTexture2DMultisample {
     width: u32,
     height: u32,
     number_of_samples: u32,
+    has_stencil: bool,
 }
 
 impl DepthTexture2DMultisample {
@@ -26,6 +27,7 @@ impl DepthTexture2DMultisample {
             width,
             height,
             number_of_samples,
+            has_stencil: T::has_stencil(),
         };
         texture.bind();
         // CHECK: Omitted `set_parameters` since neither filtering, nor mipmap levels, nor clamping makes sense for multisampled textures.
@@ -60,7 +62,11 @@ impl DepthTexture2DMultisample {
         unsafe {
             self.context.framebuffer_renderbuffer(
                 crate::context::FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                if self.has_stencil {
+                    crate::context::DEPTH_STENCIL_ATTACHMENT
+                } else {
+                    crate::context::DEPTH_ATTACHMENT
+                },
                 crate::context::RENDERBUFFER,
                 Some(self.id),
             );
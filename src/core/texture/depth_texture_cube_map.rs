@@ -8,6 +8,7 @@ pub struct DepthTextureCubeMap {
     id: crate::context::Texture,
     width: u32,
     height: u32,
+    has_stencil: bool,
 }
 
 impl DepthTextureCubeMap {
@@ -28,6 +29,7 @@ impl DepthTextureCubeMap {
             id,
             width,
             height,
+            has_stencil: T::has_stencil(),
         };
         texture.bind();
         set_parameters(
@@ -74,7 +76,11 @@ impl DepthTextureCubeMap {
         unsafe {
             self.context.framebuffer_texture_2d(
                 crate::context::DRAW_FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                if self.has_stencil {
+                    crate::context::DEPTH_STENCIL_ATTACHMENT
+                } else {
+                    crate::context::DEPTH_ATTACHMENT
+                },
                 side.to_const(),
                 Some(self.id),
                 0,
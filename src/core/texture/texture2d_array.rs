@@ -14,6 +14,7 @@ pub struct Texture2DArray {
     depth: u32,
     number_of_mip_maps: u32,
     data_byte_size: usize,
+    sampler_type: SamplerType,
 }
 
 impl Texture2DArray {
@@ -171,13 +172,14 @@ impl Texture2DArray {
             depth,
             number_of_mip_maps,
             data_byte_size: std::mem::size_of::<T>(),
+            sampler_type: SamplerType::from_data_type::<T>(),
         };
         texture.bind();
         set_parameters(
             context,
             crate::context::TEXTURE_2D_ARRAY,
-            min_filter,
-            mag_filter,
+            interpolation_for::<T>(min_filter),
+            interpolation_for::<T>(mag_filter),
             if number_of_mip_maps == 1 {
                 None
             } else {
@@ -281,6 +283,13 @@ impl Texture2DArray {
         self.depth
     }
 
+    ///
+    /// Returns the type of sampler needed to sample this texture in a shader, which depends on the [TextureDataType] used at construction.
+    ///
+    pub fn sampler_type(&self) -> SamplerType {
+        self.sampler_type
+    }
+
     pub(in crate::core) fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
@@ -10,6 +10,7 @@ pub struct Texture3D {
     depth: u32,
     number_of_mip_maps: u32,
     data_byte_size: usize,
+    sampler_type: SamplerType,
 }
 
 impl Texture3D {
@@ -84,13 +85,14 @@ impl Texture3D {
             depth,
             number_of_mip_maps,
             data_byte_size: std::mem::size_of::<T>(),
+            sampler_type: SamplerType::from_data_type::<T>(),
         };
         texture.bind();
         set_parameters(
             context,
             crate::context::TEXTURE_3D,
-            min_filter,
-            mag_filter,
+            interpolation_for::<T>(min_filter),
+            interpolation_for::<T>(mag_filter),
             if number_of_mip_maps == 1 {
                 None
             } else {
@@ -163,6 +165,13 @@ impl Texture3D {
         self.depth
     }
 
+    ///
+    /// Returns the type of sampler needed to sample this texture in a shader, which depends on the [TextureDataType] used at construction.
+    ///
+    pub fn sampler_type(&self) -> SamplerType {
+        self.sampler_type
+    }
+
     fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
@@ -189,6 +189,20 @@ impl GUI {
                             None
                         }
                     }
+                    Event::Pinch { delta, handled, .. } => {
+                        if !handled {
+                            Some(egui::Event::Zoom((delta / 200.0).exp()))
+                        } else {
+                            None
+                        }
+                    }
+                    Event::TwoFingerPan { delta, handled, .. } => {
+                        if !handled {
+                            Some(egui::Event::Scroll(egui::Vec2::new(delta.0, delta.1)))
+                        } else {
+                            None
+                        }
+                    }
                     _ => None,
                 })
                 .collect::<Vec<_>>(),
@@ -225,6 +239,16 @@ impl GUI {
                     } => {
                         *handled = true;
                     }
+                    Event::Pinch {
+                        ref mut handled, ..
+                    } => {
+                        *handled = true;
+                    }
+                    Event::TwoFingerPan {
+                        ref mut handled, ..
+                    } => {
+                        *handled = true;
+                    }
                     _ => {}
                 }
             }
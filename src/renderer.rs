@@ -29,6 +29,10 @@ pub enum RendererError {
     InvalidBufferLength(String, usize, usize),
     #[error("the material {0} is required by the geometry {1} but could not be found")]
     MissingMaterial(String, String),
+    #[error("{0}")]
+    ShaderCompilation(String),
+    #[error("failed reading shader source from {0}: {1}")]
+    ShaderSourceIo(String, std::io::Error),
 }
 
 mod camera;
@@ -151,6 +155,38 @@ macro_rules! impl_render_target_extensions_body {
             self
         }
 
+        ///
+        /// Same as [Self::render] except that an error is returned if the shader program for any of the objects fails to compile, instead of panicking.
+        /// The objects that can be rendered are still rendered. If the error fallback is enabled (see [ProgramCache::set_error_fallback]),
+        /// the objects that cannot be rendered are rendered in magenta and the error is still returned.
+        ///
+        pub fn try_render(
+            &self,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+        ) -> Result<&Self, RendererError> {
+            self.try_render_partially(self.scissor_box(), camera, objects, lights)
+        }
+
+        ///
+        /// Same as [Self::render_partially] except that an error is returned if the shader program for any of the objects fails to compile, instead of panicking.
+        /// The objects that can be rendered are still rendered. If the error fallback is enabled (see [ProgramCache::set_error_fallback]),
+        /// the objects that cannot be rendered are rendered in magenta and the error is still returned.
+        ///
+        pub fn try_render_partially(
+            &self,
+            scissor_box: ScissorBox,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+        ) -> Result<&Self, RendererError> {
+            collect_render_errors(|| {
+                self.render_partially(scissor_box, camera, objects, lights);
+            })?;
+            Ok(self)
+        }
+
         ///
         /// Render the geometries with the given [Material] using the given camera and lights into this render target.
         /// Use an empty array for the `lights` argument, if the material does not require lights to be rendered.
@@ -336,6 +372,302 @@ macro_rules! impl_render_target_extensions_body {
             .unwrap();
             self
         }
+
+        ///
+        /// Draws an outline, as defined by the given [OutlineEffect], around the given objects on top of what is already rendered into this render target.
+        /// The objects are rendered into an outline mask which is then composited onto this render target.
+        /// If the depth texture of the already rendered scene is given, the parts of the outline that are hidden behind other objects
+        /// are drawn with the [OutlineEffect::occluded_color] (or not at all if that is `None`), otherwise the entire outline is visible.
+        ///
+        /// **Note:** The depth texture must have the same size as the viewport of the camera.
+        ///
+        pub fn render_outline(
+            &self,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            outline: &OutlineEffect,
+            depth_texture: Option<DepthTexture>,
+        ) -> &Self {
+            self.render_outline_partially(
+                self.scissor_box(),
+                camera,
+                objects,
+                outline,
+                depth_texture,
+            )
+        }
+
+        ///
+        /// Draws an outline, as defined by the given [OutlineEffect], around the given objects on top of what is already rendered into the part of this render target defined by the scissor box.
+        /// See [RenderTarget::render_outline] for more information.
+        ///
+        pub fn render_outline_partially(
+            &self,
+            scissor_box: ScissorBox,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            outline: &OutlineEffect,
+            depth_texture: Option<DepthTexture>,
+        ) -> &Self {
+            let mut mask_camera = camera.clone();
+            let viewport =
+                Viewport::new_at_origo(camera.viewport().width, camera.viewport().height);
+            mask_camera.set_viewport(viewport);
+            let mut mask_texture = Texture2D::new_empty::<[f32; 2]>(
+                &self.context,
+                viewport.width,
+                viewport.height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            let mut mask_depth_texture = DepthTexture2D::new::<f32>(
+                &self.context,
+                viewport.width,
+                viewport.height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            RenderTarget::new(
+                mask_texture.as_color_target(None),
+                mask_depth_texture.as_depth_target(),
+            )
+            .clear(ClearState::color_and_depth(0.0, 1.0, 0.0, 0.0, 1.0))
+            .write::<RendererError>(|| {
+                for object in objects
+                    .into_iter()
+                    .filter(|o| mask_camera.in_frustum(&o.aabb()))
+                {
+                    render_with_material(
+                        &self.context,
+                        &mask_camera,
+                        object,
+                        OutlineMaskMaterial {},
+                        &[],
+                    );
+                }
+                Ok(())
+            })
+            .unwrap();
+
+            self.apply_screen_effect_partially(
+                scissor_box,
+                outline,
+                camera,
+                &[],
+                Some(ColorTexture::Single(&mask_texture)),
+                depth_texture,
+            )
+        }
+
+        ///
+        /// Computes screen space ambient occlusion, as defined by the given [SsaoEffect], from the depth texture of the already rendered scene,
+        /// blurs it with a depth aware blur and multiplies it onto this render target.
+        /// If a normal texture is given, it must contain the normals of the scene encoded as in the g-buffer produced by the [DeferredPhysicalMaterial],
+        /// otherwise the normals are reconstructed from the depth.
+        ///
+        /// **Note:** The depth and normal texture must have the same size as the viewport of the camera.
+        ///
+        pub fn apply_ssao(
+            &self,
+            camera: &Camera,
+            ssao: &SsaoEffect,
+            depth_texture: DepthTexture,
+            normal_texture: Option<ColorTexture>,
+        ) -> &Self {
+            self.apply_ssao_partially(
+                self.scissor_box(),
+                camera,
+                ssao,
+                depth_texture,
+                normal_texture,
+            )
+        }
+
+        ///
+        /// Computes screen space ambient occlusion, as defined by the given [SsaoEffect], and multiplies it onto the part of this render target defined by the scissor box.
+        /// See [RenderTarget::apply_ssao] for more information.
+        ///
+        pub fn apply_ssao_partially(
+            &self,
+            scissor_box: ScissorBox,
+            camera: &Camera,
+            ssao: &SsaoEffect,
+            depth_texture: DepthTexture,
+            normal_texture: Option<ColorTexture>,
+        ) -> &Self {
+            if ssao.blur_radius == 0 {
+                return self.apply_screen_effect_partially(
+                    scissor_box,
+                    ssao,
+                    camera,
+                    &[],
+                    normal_texture,
+                    Some(depth_texture),
+                );
+            }
+            let mut ao_camera = camera.clone();
+            let viewport =
+                Viewport::new_at_origo(camera.viewport().width, camera.viewport().height);
+            ao_camera.set_viewport(viewport);
+            let mut ao_texture = Texture2D::new_empty::<u8>(
+                &self.context,
+                viewport.width,
+                viewport.height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            ao_texture
+                .as_color_target(None)
+                .clear(ClearState::color(1.0, 1.0, 1.0, 1.0))
+                .apply_screen_effect(ssao, &ao_camera, &[], normal_texture, Some(depth_texture));
+            self.apply_screen_effect_partially(
+                scissor_box,
+                &SsaoBlurEffect {
+                    radius: ssao.blur_radius,
+                },
+                camera,
+                &[],
+                Some(ColorTexture::Single(&ao_texture)),
+                Some(depth_texture),
+            )
+        }
+
+        ///
+        /// Applies bloom, as defined by the given [BloomEffect], to the given high dynamic range color texture and writes the result,
+        /// with the tone and color mapping of the camera applied, into this render target.
+        ///
+        /// **Note:** The color texture must have the same size as the viewport of the camera.
+        ///
+        pub fn apply_bloom(
+            &self,
+            camera: &Camera,
+            bloom: &BloomEffect,
+            color_texture: ColorTexture,
+        ) -> &Self {
+            self.apply_bloom_partially(self.scissor_box(), camera, bloom, color_texture)
+        }
+
+        ///
+        /// Applies bloom, as defined by the given [BloomEffect], to the given high dynamic range color texture and writes the result into the part of this render target defined by the scissor box.
+        /// See [RenderTarget::apply_bloom] for more information.
+        ///
+        pub fn apply_bloom_partially(
+            &self,
+            scissor_box: ScissorBox,
+            camera: &Camera,
+            bloom: &BloomEffect,
+            color_texture: ColorTexture,
+        ) -> &Self {
+            // The mip levels alternate between two textures to avoid reading from and writing to the same texture
+            let width = (camera.viewport().width / 2).max(1);
+            let height = (camera.viewport().height / 2).max(1);
+            let levels = BloomEffect::levels(width, height);
+            let new_texture = || {
+                Texture2D::new_empty::<[f16; 4]>(
+                    &self.context,
+                    width,
+                    height,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    Some(Interpolation::Nearest),
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                )
+            };
+            let mut even_texture = new_texture();
+            let mut odd_texture = new_texture();
+            let level_camera = |level: u32| {
+                let mut level_camera = camera.clone();
+                level_camera.set_viewport(Viewport::new_at_origo(
+                    (width >> level).max(1),
+                    (height >> level).max(1),
+                ));
+                level_camera
+            };
+
+            // Extract the bright parts
+            even_texture
+                .as_color_target(Some(0))
+                .clear(ClearState::color(0.0, 0.0, 0.0, 1.0))
+                .apply_screen_effect(
+                    &BloomPassEffect {
+                        pass: BloomPass::Prefilter,
+                        bloom,
+                        bloom_texture: None,
+                        source_level: 0,
+                    },
+                    &level_camera(0),
+                    &[],
+                    Some(color_texture),
+                    None,
+                );
+
+            // Downsample
+            for level in 1..levels {
+                let (target, source) = if level % 2 == 0 {
+                    (&mut even_texture, &odd_texture)
+                } else {
+                    (&mut odd_texture, &even_texture)
+                };
+                target
+                    .as_color_target(Some(level))
+                    .clear(ClearState::color(0.0, 0.0, 0.0, 1.0))
+                    .apply_screen_effect(
+                        &BloomPassEffect {
+                            pass: BloomPass::Downsample,
+                            bloom,
+                            bloom_texture: Some(source),
+                            source_level: level - 1,
+                        },
+                        &level_camera(level),
+                        &[],
+                        None,
+                        None,
+                    );
+            }
+
+            // Upsample and accumulate
+            for level in (0..levels - 1).rev() {
+                let (target, source) = if level % 2 == 0 {
+                    (&mut even_texture, &odd_texture)
+                } else {
+                    (&mut odd_texture, &even_texture)
+                };
+                target.as_color_target(Some(level)).apply_screen_effect(
+                    &BloomPassEffect {
+                        pass: BloomPass::Upsample,
+                        bloom,
+                        bloom_texture: Some(source),
+                        source_level: level + 1,
+                    },
+                    &level_camera(level),
+                    &[],
+                    None,
+                    None,
+                );
+            }
+
+            // Composite
+            self.apply_screen_effect_partially(
+                scissor_box,
+                &BloomPassEffect {
+                    pass: BloomPass::Composite,
+                    bloom,
+                    bloom_texture: Some(&even_texture),
+                    source_level: 0,
+                },
+                camera,
+                &[],
+                Some(color_texture),
+                None,
+            )
+        }
     };
 }
 
@@ -380,6 +712,9 @@ impl_render_target_extensions!(DepthTargetMultisample<D: DepthTextureDataType>);
 /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
 /// Use an empty array for the `lights` argument, if the material does not require lights to be rendered.
 ///
+/// Panics if the shader program fails to compile, unless the error fallback is enabled (see [ProgramCache::set_error_fallback]),
+/// in which case the geometry is rendered in magenta. Use [try_render_with_material] to handle the error instead.
+///
 pub fn render_with_material(
     context: &Context,
     camera: &Camera,
@@ -387,27 +722,90 @@ pub fn render_with_material(
     material: impl Material,
     lights: &[&dyn Light],
 ) {
-    let fragment_attributes = material.fragment_attributes();
-    let mut id = geometry.id(fragment_attributes).to_le_bytes().to_vec();
-    id.extend(material.id().to_le_bytes());
-    id.extend(lights.iter().map(|l| l.id()));
+    if let Err(error) = try_render_with_material(context, camera, &geometry, material, lights) {
+        handle_render_error(context, error, || {
+            try_render_with_material(context, camera, &geometry, ErrorMaterial, &[])
+        });
+    }
+}
 
+///
+/// Same as [render_with_material] except that an error is returned if the shader program fails to compile.
+/// The error contains the compilation log where the line numbers are mapped back to the geometry, material and light source code.
+///
+pub fn try_render_with_material(
+    context: &Context,
+    camera: &Camera,
+    geometry: impl Geometry,
+    material: impl Material,
+    lights: &[&dyn Light],
+) -> Result<(), RendererError> {
+    let fragment_attributes = material.fragment_attributes();
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
-        Program::from_source(
-            context,
-            &geometry.vertex_shader_source(fragment_attributes),
-            &material.fragment_shader_source(lights),
-        )
-        .expect("Failed compiling shader")
-    });
+    let program = try_get_program(
+        &mut programs,
+        material_program_id(&geometry, &material, lights),
+        || {
+            let vertex_shader_source = geometry.vertex_shader_source(fragment_attributes);
+            let fragment_shader_source = material.fragment_shader_source(lights);
+            Program::from_source(context, &vertex_shader_source, &fragment_shader_source).map_err(
+                |e| {
+                    shader_error(
+                        e,
+                        ("geometry", &vertex_shader_source),
+                        ("material", &fragment_shader_source),
+                        lights,
+                    )
+                },
+            )
+        },
+    )?;
     material.use_uniforms(program, camera, lights);
+    use_lights_uniform_block(program, lights);
     geometry.draw(
         camera,
         program,
         material.render_states(),
         fragment_attributes,
     );
+    Ok(())
+}
+
+///
+/// Compiles the programs needed for rendering each of the given combinations of [Geometry], [Material] and lights with [render_with_material]
+/// and inserts them into the program cache (see [Context::programs]) unless they are already there.
+/// This can for example be used while loading to avoid stalls the first time a combination is rendered.
+///
+pub fn warm_up_programs<'a>(
+    context: &Context,
+    combinations: impl IntoIterator<Item = (&'a dyn Geometry, &'a dyn Material, &'a [&'a dyn Light])>,
+) {
+    let mut programs = context.programs.write().unwrap();
+    for (geometry, material, lights) in combinations {
+        let fragment_attributes = material.fragment_attributes();
+        programs.get_or_insert_with(material_program_id(&geometry, &material, lights), || {
+            Program::from_source(
+                context,
+                &geometry.vertex_shader_source(fragment_attributes),
+                &material.fragment_shader_source(lights),
+            )
+            .expect("Failed compiling shader")
+        });
+    }
+}
+
+fn material_program_id(
+    geometry: &impl Geometry,
+    material: &impl Material,
+    lights: &[&dyn Light],
+) -> Vec<u8> {
+    let mut id = geometry
+        .id(material.fragment_attributes())
+        .to_le_bytes()
+        .to_vec();
+    id.extend(material.id().to_le_bytes());
+    id.extend(lights.iter().map(|l| l.id()));
+    id
 }
 
 ///
@@ -415,6 +813,9 @@ pub fn render_with_material(
 /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
 /// Use an empty array for the `lights` argument, if the effect does not require lights to be rendered.
 ///
+/// Panics if the shader program fails to compile, unless the error fallback is enabled (see [ProgramCache::set_error_fallback]),
+/// in which case the geometry is rendered in magenta. Use [try_render_with_effect] to handle the error instead.
+///
 pub fn render_with_effect(
     context: &Context,
     camera: &Camera,
@@ -424,22 +825,57 @@ pub fn render_with_effect(
     color_texture: Option<ColorTexture>,
     depth_texture: Option<DepthTexture>,
 ) {
+    if let Err(error) = try_render_with_effect(
+        context,
+        camera,
+        &geometry,
+        effect,
+        lights,
+        color_texture,
+        depth_texture,
+    ) {
+        handle_render_error(context, error, || {
+            try_render_with_material(context, camera, &geometry, ErrorMaterial, &[])
+        });
+    }
+}
+
+///
+/// Same as [render_with_effect] except that an error is returned if the shader program fails to compile.
+/// The error contains the compilation log where the line numbers are mapped back to the geometry, effect and light source code.
+///
+pub fn try_render_with_effect(
+    context: &Context,
+    camera: &Camera,
+    geometry: impl Geometry,
+    effect: impl Effect,
+    lights: &[&dyn Light],
+    color_texture: Option<ColorTexture>,
+    depth_texture: Option<DepthTexture>,
+) -> Result<(), RendererError> {
     let fragment_attributes = effect.fragment_attributes();
     let mut id = geometry.id(fragment_attributes).to_le_bytes().to_vec();
     id.extend(effect.id(color_texture, depth_texture).to_le_bytes());
     id.extend(lights.iter().map(|l| l.id()));
 
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
-        Program::from_source(
-            context,
-            &geometry.vertex_shader_source(fragment_attributes),
-            &effect.fragment_shader_source(lights, color_texture, depth_texture),
-        )
-        .expect("Failed compiling shader")
-    });
+    let program = try_get_program(&mut programs, id, || {
+        let vertex_shader_source = geometry.vertex_shader_source(fragment_attributes);
+        let fragment_shader_source =
+            effect.fragment_shader_source(lights, color_texture, depth_texture);
+        Program::from_source(context, &vertex_shader_source, &fragment_shader_source).map_err(|e| {
+            shader_error(
+                e,
+                ("geometry", &vertex_shader_source),
+                ("effect", &fragment_shader_source),
+                lights,
+            )
+        })
+    })?;
     effect.use_uniforms(program, camera, lights, color_texture, depth_texture);
+    use_lights_uniform_block(program, lights);
     geometry.draw(camera, program, effect.render_states(), fragment_attributes);
+    Ok(())
 }
 
 ///
@@ -447,12 +883,32 @@ pub fn render_with_effect(
 /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
 /// Use an empty array for the `lights` argument, if the material does not require lights to be rendered.
 ///
+/// Panics if the shader program fails to compile, unless the error fallback is enabled (see [ProgramCache::set_error_fallback]),
+/// in which case the screen is filled with magenta. Use [try_apply_screen_material] to handle the error instead.
+///
 pub fn apply_screen_material(
     context: &Context,
     material: impl Material,
     camera: &Camera,
     lights: &[&dyn Light],
 ) {
+    if let Err(error) = try_apply_screen_material(context, material, camera, lights) {
+        handle_render_error(context, error, || {
+            try_apply_screen_material(context, ErrorMaterial, camera, &[])
+        });
+    }
+}
+
+///
+/// Same as [apply_screen_material] except that an error is returned if the shader program fails to compile.
+/// The error contains the compilation log where the line numbers are mapped back to the material and light source code.
+///
+pub fn try_apply_screen_material(
+    context: &Context,
+    material: impl Material,
+    camera: &Camera,
+    lights: &[&dyn Light],
+) -> Result<(), RendererError> {
     let fragment_attributes = material.fragment_attributes();
     if fragment_attributes.normal || fragment_attributes.position || fragment_attributes.tangents {
         panic!("Not possible to use the given material to render full screen, the full screen geometry only provides uv coordinates and color");
@@ -462,21 +918,31 @@ pub fn apply_screen_material(
     id.extend(lights.iter().map(|l| l.id()));
 
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
+    let program = try_get_program(&mut programs, id, || {
+        let fragment_shader_source = material.fragment_shader_source(lights);
         Program::from_source(
             context,
             full_screen_vertex_shader_source(),
-            &material.fragment_shader_source(lights),
+            &fragment_shader_source,
         )
-        .expect("Failed compiling shader")
-    });
+        .map_err(|e| {
+            shader_error(
+                e,
+                ("full screen geometry", full_screen_vertex_shader_source()),
+                ("material", &fragment_shader_source),
+                lights,
+            )
+        })
+    })?;
     material.use_uniforms(program, camera, lights);
+    use_lights_uniform_block(program, lights);
     full_screen_draw(
         context,
         program,
         material.render_states(),
         camera.viewport(),
     );
+    Ok(())
 }
 
 ///
@@ -484,6 +950,9 @@ pub fn apply_screen_material(
 /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
 /// Use an empty array for the `lights` argument, if the effect does not require lights to be rendered.
 ///
+/// Panics if the shader program fails to compile, unless the error fallback is enabled (see [ProgramCache::set_error_fallback]),
+/// in which case the screen is filled with magenta. Use [try_apply_screen_effect] to handle the error instead.
+///
 pub fn apply_screen_effect(
     context: &Context,
     effect: impl Effect,
@@ -492,6 +961,32 @@ pub fn apply_screen_effect(
     color_texture: Option<ColorTexture>,
     depth_texture: Option<DepthTexture>,
 ) {
+    if let Err(error) = try_apply_screen_effect(
+        context,
+        effect,
+        camera,
+        lights,
+        color_texture,
+        depth_texture,
+    ) {
+        handle_render_error(context, error, || {
+            try_apply_screen_material(context, ErrorMaterial, camera, &[])
+        });
+    }
+}
+
+///
+/// Same as [apply_screen_effect] except that an error is returned if the shader program fails to compile.
+/// The error contains the compilation log where the line numbers are mapped back to the effect and light source code.
+///
+pub fn try_apply_screen_effect(
+    context: &Context,
+    effect: impl Effect,
+    camera: &Camera,
+    lights: &[&dyn Light],
+    color_texture: Option<ColorTexture>,
+    depth_texture: Option<DepthTexture>,
+) -> Result<(), RendererError> {
     let fragment_attributes = effect.fragment_attributes();
     if fragment_attributes.normal || fragment_attributes.position || fragment_attributes.tangents {
         panic!("Not possible to use the given effect to render full screen, the full screen geometry only provides uv coordinates and color");
@@ -501,16 +996,179 @@ pub fn apply_screen_effect(
     id.extend(lights.iter().map(|l| l.id()));
 
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
+    let program = try_get_program(&mut programs, id, || {
+        let fragment_shader_source =
+            effect.fragment_shader_source(lights, color_texture, depth_texture);
         Program::from_source(
             context,
             full_screen_vertex_shader_source(),
-            &effect.fragment_shader_source(lights, color_texture, depth_texture),
+            &fragment_shader_source,
         )
-        .expect("Failed compiling shader")
-    });
+        .map_err(|e| {
+            shader_error(
+                e,
+                ("full screen geometry", full_screen_vertex_shader_source()),
+                ("effect", &fragment_shader_source),
+                lights,
+            )
+        })
+    })?;
     effect.use_uniforms(program, camera, lights, color_texture, depth_texture);
+    use_lights_uniform_block(program, lights);
     full_screen_draw(context, program, effect.render_states(), camera.viewport());
+    Ok(())
+}
+
+///
+/// Returns the program with the given id from the cache or compiles it using the given callback.
+/// If the program previously failed to compile, the registered failure is returned without trying to compile it again.
+///
+fn try_get_program<'a>(
+    programs: &'a mut ProgramCache,
+    id: Vec<u8>,
+    create: impl FnOnce() -> Result<Program, RendererError>,
+) -> Result<&'a Program, RendererError> {
+    if let Some(message) = programs.failure(&id) {
+        return Err(RendererError::ShaderCompilation(message.to_owned()));
+    }
+    let result = programs
+        .try_get_or_insert_with(id.clone(), create)
+        .map(|_| ());
+    if let Err(error) = result {
+        if let RendererError::ShaderCompilation(message) = &error {
+            programs.insert_failure(id, message.clone());
+        }
+        return Err(error);
+    }
+    Ok(programs.peek(&id).unwrap())
+}
+
+thread_local! {
+    static COLLECTED_ERRORS: std::cell::RefCell<Option<Vec<RendererError>>> = const { std::cell::RefCell::new(None) };
+}
+
+///
+/// Calls the given render callback and returns the first error that occurred in any of the render calls made inside the callback
+/// instead of panicking (see [handle_render_error]).
+///
+fn collect_render_errors(render: impl FnOnce()) -> Result<(), RendererError> {
+    let previous = COLLECTED_ERRORS.with(|errors| errors.replace(Some(Vec::new())));
+    render();
+    let errors = COLLECTED_ERRORS.with(|errors| errors.replace(previous));
+    match errors.and_then(|errors| errors.into_iter().next()) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+///
+/// Handles an error from one of the infallible render functions.
+/// The error is collected if inside [collect_render_errors] and otherwise it panics, unless the error fallback is enabled in which case the fallback is rendered instead.
+///
+fn handle_render_error(
+    context: &Context,
+    error: RendererError,
+    fallback: impl FnOnce() -> Result<(), RendererError>,
+) {
+    let error = COLLECTED_ERRORS.with(|errors| match errors.borrow_mut().as_mut() {
+        Some(errors) => {
+            errors.push(error);
+            None
+        }
+        None => Some(error),
+    });
+    if context.programs.read().unwrap().error_fallback() {
+        // If the fallback also fails to compile, for example because of an error in the geometry, nothing is rendered.
+        fallback().ok();
+    } else if let Some(error) = error {
+        panic!("{}", error);
+    }
+}
+
+///
+/// Converts an error from [Program::from_source] to a [RendererError] where the line numbers in the compilation log are mapped back to the given
+/// vertex and fragment shader source code (each given together with a name) and the source code of the lights.
+///
+pub(in crate::renderer) fn shader_error(
+    error: CoreError,
+    vertex_shader: (&str, &str),
+    fragment_shader: (&str, &str),
+    lights: &[&dyn Light],
+) -> RendererError {
+    match error {
+        CoreError::ShaderCompilation(stage, log, source) => {
+            let mut fragments = Vec::new();
+            if stage == "fragment" {
+                for (i, light) in lights.iter().enumerate() {
+                    fragments.push((format!("light {}", i), light.shader_source(i as u32)));
+                }
+                fragments.push((fragment_shader.0.to_owned(), fragment_shader.1.to_owned()));
+            } else {
+                fragments.push((vertex_shader.0.to_owned(), vertex_shader.1.to_owned()));
+            }
+            let mut message = String::new();
+            for line in log.lines() {
+                message.push_str(line);
+                if let Some(location) = log_line_number(line).and_then(|line_number| {
+                    fragments.iter().find_map(|(name, fragment)| {
+                        source_line_number(&source, fragment, line_number)
+                            .map(|l| format!(" [{} line {}]", name, l))
+                    })
+                }) {
+                    message.push_str(&location);
+                }
+                message.push('\n');
+            }
+            RendererError::ShaderCompilation(format!(
+                "failed compiling {} shader:\n{}",
+                stage, message
+            ))
+        }
+        CoreError::ShaderLink(log) => {
+            RendererError::ShaderCompilation(format!("failed to link shader program:\n{}", log))
+        }
+        e => RendererError::ShaderCompilation(e.to_string()),
+    }
+}
+
+///
+/// Returns the line number in the given fragment of the source if the given line number in the full source is inside the fragment.
+///
+fn source_line_number(source: &str, fragment: &str, line_number: usize) -> Option<usize> {
+    if fragment.is_empty() {
+        return None;
+    }
+    let start = source[..source.find(fragment)?].matches('\n').count() + 1;
+    let end = start + fragment.lines().count();
+    if (start..end).contains(&line_number) {
+        Some(line_number - start + 1)
+    } else {
+        None
+    }
+}
+
+///
+/// Returns the line number referred to in a line of a shader compilation log, for example `12` in
+/// `0:12(5): error: 'foo' undeclared` (Mesa), `ERROR: 0:12: 'foo' : undeclared identifier` (ANGLE and others)
+/// or `0(12) : error C1008: undefined variable "foo"` (NVIDIA).
+/// The formats are tried in that order, since the Mesa format also contains the column in parentheses.
+///
+fn log_line_number(line: &str) -> Option<usize> {
+    for (open, close) in [(':', '('), (':', ':'), ('(', ')')] {
+        for (i, _) in line.match_indices(open) {
+            if !line[..i].ends_with(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+            let rest = &line[i + 1..];
+            if let Some(line_number) = rest
+                .find(close)
+                .and_then(|end| rest[..end].trim().parse::<usize>().ok())
+            {
+                return Some(line_number);
+            }
+        }
+    }
+    None
 }
 
 ///
@@ -640,3 +1298,485 @@ pub fn ray_intersect(
         None
     }
 }
+
+///
+/// The result of [pick_with_info] or [ray_intersect_with_info] which contains information about the closest intersection.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PickResult {
+    /// The position of the intersection in world space.
+    pub position: Vec3,
+    /// The geometric normal of the intersected triangle in world space, pointing towards the start of the ray.
+    pub normal: Vec3,
+    /// The index of the intersected geometry in the list of geometries given as input.
+    pub geometry_index: u32,
+    /// The index of the intersected instance if the intersected geometry is instanced (for example an [InstancedMesh]), otherwise zero.
+    /// Is `None` if the geometry does not output the instance id (see [Geometry::outputs_instance_id]), in which case the instance is unknown.
+    pub instance_index: Option<u32>,
+    /// The index of the intersected triangle.
+    /// Is always `None` on web and other OpenGL ES platforms where the primitive id is not available in the fragment shader.
+    pub triangle_index: Option<u32>,
+    /// The uv coordinates at the intersection, only available if requested.
+    pub uv: Option<Vec2>,
+    /// The distance from the start of the ray to the intersection.
+    pub depth: f32,
+}
+
+///
+/// Same as [pick] except that it returns additional information about the intersection, see [PickResult].
+/// If `uv` is true, the uv coordinates at the intersection is also calculated, in which case all of the geometries must provide uv coordinates.
+///
+pub fn pick_with_info(
+    context: &Context,
+    camera: &Camera,
+    pixel: impl Into<PhysicalPoint> + Copy,
+    geometries: impl IntoIterator<Item = impl Geometry>,
+    uv: bool,
+) -> Option<PickResult> {
+    let pos = camera.position_at_pixel(pixel);
+    let dir = camera.view_direction_at_pixel(pixel);
+    ray_intersect_with_info(
+        context,
+        pos + dir * camera.z_near(),
+        dir,
+        camera.z_far() - camera.z_near(),
+        geometries,
+        uv,
+    )
+}
+
+///
+/// Same as [ray_intersect] except that it returns additional information about the intersection, see [PickResult].
+/// If `uv` is true, the uv coordinates at the intersection is also calculated, in which case all of the geometries must provide uv coordinates.
+///
+pub fn ray_intersect_with_info(
+    context: &Context,
+    position: Vec3,
+    direction: Vec3,
+    max_depth: f32,
+    geometries: impl IntoIterator<Item = impl Geometry>,
+    uv: bool,
+) -> Option<PickResult> {
+    let viewport = Viewport::new_at_origo(1, 1);
+    let up = if direction.dot(vec3(1.0, 0.0, 0.0)).abs() > 0.99 {
+        direction.cross(vec3(0.0, 1.0, 0.0))
+    } else {
+        direction.cross(vec3(1.0, 0.0, 0.0))
+    };
+    let camera = Camera::new_orthographic(
+        viewport,
+        position,
+        position + direction * max_depth,
+        up,
+        0.01,
+        0.0,
+        max_depth,
+    );
+    let mut texture = Texture2DArray::new_empty::<[f32; 4]>(
+        context,
+        viewport.width,
+        viewport.height,
+        3,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        context,
+        viewport.width,
+        viewport.height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let layers = [0, 1, 2];
+    RenderTarget::new(
+        texture.as_color_target(&layers, None),
+        depth_texture.as_depth_target(),
+    )
+    .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
+    .write::<RendererError>(|| {
+        for (geometry_index, geometry) in geometries.into_iter().enumerate() {
+            let material = PickMaterial {
+                geometry_index: geometry_index as u32,
+                uv,
+                instance_id: geometry.outputs_instance_id(),
+                max_distance: max_depth,
+            };
+            render_with_material(context, &camera, &geometry, &material, &[]);
+        }
+        Ok(())
+    })
+    .unwrap();
+
+    let ids = texture.as_color_target(&[0], None).read::<[f32; 4]>()[0];
+    if ids[0] >= 1.0 {
+        return None;
+    }
+    let normal = texture.as_color_target(&[1], None).read::<[f32; 4]>()[0];
+    let uv = if uv {
+        let uv = texture.as_color_target(&[2], None).read::<[f32; 4]>()[0];
+        // The v coordinate is flipped when sent to the GPU
+        Some(vec2(uv[0], 1.0 - uv[1]))
+    } else {
+        None
+    };
+    let depth = ids[0] * max_depth;
+    Some(PickResult {
+        position: position + direction * depth,
+        normal: vec3(normal[0], normal[1], normal[2]),
+        geometry_index: ids[1] as u32,
+        instance_index: if ids[2] < 0.0 {
+            None
+        } else {
+            Some(ids[2] as u32)
+        },
+        triangle_index: if ids[3] < 0.0 {
+            None
+        } else {
+            Some(ids[3] as u32)
+        },
+        uv,
+        depth,
+    })
+}
+
+///
+/// An area of the screen used for selecting objects, see [select].
+/// All values should be given in physical pixels, with origo in the bottom left corner of the render target.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectionArea {
+    /// A rectangular area.
+    Rectangle(ScissorBox),
+    /// A polygonal area (for example a lasso) defined by its corners in order.
+    Polygon(Vec<PhysicalPoint>),
+}
+
+impl From<ScissorBox> for SelectionArea {
+    fn from(scissor_box: ScissorBox) -> Self {
+        Self::Rectangle(scissor_box)
+    }
+}
+
+impl From<Vec<PhysicalPoint>> for SelectionArea {
+    fn from(points: Vec<PhysicalPoint>) -> Self {
+        Self::Polygon(points)
+    }
+}
+
+impl SelectionArea {
+    ///
+    /// Returns the smallest rectangle that contains the entire area.
+    ///
+    pub fn bounding_box(&self) -> ScissorBox {
+        match self {
+            Self::Rectangle(scissor_box) => *scissor_box,
+            Self::Polygon(points) => {
+                if points.is_empty() {
+                    return ScissorBox::new_at_origo(0, 0);
+                }
+                let (min, max) = points.iter().fold(
+                    (
+                        vec2(f32::INFINITY, f32::INFINITY),
+                        vec2(f32::NEG_INFINITY, f32::NEG_INFINITY),
+                    ),
+                    |(min, max), p| {
+                        (
+                            vec2(min.x.min(p.x), min.y.min(p.y)),
+                            vec2(max.x.max(p.x), max.y.max(p.y)),
+                        )
+                    },
+                );
+                let x = min.x.floor() as i32;
+                let y = min.y.floor() as i32;
+                ScissorBox {
+                    x,
+                    y,
+                    width: (max.x.ceil() as i32 - x).max(0) as u32,
+                    height: (max.y.ceil() as i32 - y).max(0) as u32,
+                }
+            }
+        }
+    }
+
+    ///
+    /// Returns whether or not the given point is inside the area.
+    /// Uses the even-odd rule for self-intersecting polygons.
+    ///
+    pub fn contains(&self, point: impl Into<PhysicalPoint>) -> bool {
+        let point = point.into();
+        match self {
+            Self::Rectangle(scissor_box) => {
+                point.x >= scissor_box.x as f32
+                    && point.x < (scissor_box.x + scissor_box.width as i32) as f32
+                    && point.y >= scissor_box.y as f32
+                    && point.y < (scissor_box.y + scissor_box.height as i32) as f32
+            }
+            Self::Polygon(points) => {
+                let mut inside = false;
+                let mut j = points.len().wrapping_sub(1);
+                for i in 0..points.len() {
+                    let (a, b) = (points[i], points[j]);
+                    if (a.y > point.y) != (b.y > point.y)
+                        && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+                    {
+                        inside = !inside;
+                    }
+                    j = i;
+                }
+                inside
+            }
+        }
+    }
+}
+
+///
+/// Finds the objects inside the given [SelectionArea] (a rectangle or a lasso polygon) as seen from the given camera.
+/// Returns the set of `(object index, instance index)` pairs, where the object index is the index into the list of objects given as input
+/// and the instance index is the index of the selected instance if the object is instanced (for example an [InstancedMesh]), otherwise zero.
+/// The instance index is `None` if the object does not output the instance id (see [Geometry::outputs_instance_id]).
+///
+/// The selection is done on the GPU by rendering an id buffer, so only objects that are visible inside the area are found.
+/// If `include_occluded` is true, objects whose [Geometry::aabb] intersects the sub-frustum spanned by the bounding rectangle of the area
+/// are also included. Those objects are returned with `None` as instance index, since it is not known which of their instances are inside the area.
+///
+pub fn select(
+    context: &Context,
+    camera: &Camera,
+    area: impl Into<SelectionArea>,
+    objects: impl IntoIterator<Item = impl Geometry>,
+    include_occluded: bool,
+) -> std::collections::HashSet<(u32, Option<u32>)> {
+    let area = area.into();
+    let objects = objects.into_iter().collect::<Vec<_>>();
+    let viewport = camera.viewport();
+    let scissor_box = area.bounding_box().intersection(viewport);
+    let mut selected = std::collections::HashSet::new();
+    if scissor_box.width == 0 || scissor_box.height == 0 {
+        return selected;
+    }
+
+    // Only the pixels inside the bounding rectangle of the area are rendered, so the viewport is moved such that the rectangle starts in origo
+    let mut selection_camera = camera.clone();
+    selection_camera.set_viewport(Viewport {
+        x: viewport.x - scissor_box.x,
+        y: viewport.y - scissor_box.y,
+        width: viewport.width,
+        height: viewport.height,
+    });
+    let mut texture = Texture2D::new_empty::<[u32; 4]>(
+        context,
+        scissor_box.width,
+        scissor_box.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        context,
+        scissor_box.width,
+        scissor_box.height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let target = RenderTarget::new(
+        texture.as_color_target(None),
+        depth_texture.as_depth_target(),
+    );
+    target
+        .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
+        .write::<RendererError>(|| {
+            for (object_index, object) in objects.iter().enumerate() {
+                let material = SelectionMaterial {
+                    object_index: object_index as u32,
+                    instance_id: object.outputs_instance_id(),
+                };
+                render_with_material(context, &selection_camera, object, &material, &[]);
+            }
+            Ok(())
+        })
+        .unwrap();
+
+    // The pixels are returned from the top row to the bottom row
+    let ids = target.read_color::<[u32; 4]>();
+    for (i, id) in ids.iter().enumerate() {
+        if id[0] == 0 {
+            continue;
+        }
+        let x = scissor_box.x + (i % scissor_box.width as usize) as i32;
+        let y =
+            scissor_box.y + scissor_box.height as i32 - 1 - (i / scissor_box.width as usize) as i32;
+        if area.contains(PhysicalPoint {
+            x: x as f32 + 0.5,
+            y: y as f32 + 0.5,
+        }) {
+            // The instance index is u32::MAX if the geometry does not output the instance id
+            selected.insert((id[0] - 1, Some(id[1]).filter(|i| *i != u32::MAX)));
+        }
+    }
+
+    if include_occluded {
+        let planes = selection_frustum_planes(camera, scissor_box);
+        for (object_index, object) in objects.iter().enumerate() {
+            let object_index = object_index as u32;
+            if selected.iter().any(|(i, _)| *i == object_index) {
+                continue;
+            }
+            let aabb = object.aabb();
+            if aabb.is_empty() || !camera.in_frustum(&aabb) {
+                continue;
+            }
+            let inside = aabb.is_infinite()
+                || planes.iter().all(|(normal, point)| {
+                    // The corner of the box furthest along the plane normal
+                    let corner = vec3(
+                        if normal.x > 0.0 {
+                            aabb.max().x
+                        } else {
+                            aabb.min().x
+                        },
+                        if normal.y > 0.0 {
+                            aabb.max().y
+                        } else {
+                            aabb.min().y
+                        },
+                        if normal.z > 0.0 {
+                            aabb.max().z
+                        } else {
+                            aabb.min().z
+                        },
+                    );
+                    normal.dot(corner - point) >= 0.0
+                });
+            if inside {
+                selected.insert((object_index, None));
+            }
+        }
+    }
+    selected
+}
+
+///
+/// Returns the four side planes, given as a normal pointing inwards and a point on the plane,
+/// of the part of the camera frustum that is inside the given rectangle.
+///
+fn selection_frustum_planes(camera: &Camera, scissor_box: ScissorBox) -> [(Vec3, Vec3); 4] {
+    let x0 = scissor_box.x as f32;
+    let y0 = scissor_box.y as f32;
+    let x1 = x0 + scissor_box.width as f32;
+    let y1 = y0 + scissor_box.height as f32;
+    let ray = |x: f32, y: f32| {
+        let pixel = PhysicalPoint { x, y };
+        (
+            camera.position_at_pixel(pixel),
+            camera.view_direction_at_pixel(pixel),
+        )
+    };
+    let corners = [ray(x0, y0), ray(x1, y0), ray(x1, y1), ray(x0, y1)];
+    let (center_position, center_direction) = ray(0.5 * (x0 + x1), 0.5 * (y0 + y1));
+    let center = center_position + center_direction;
+    let mut planes = [(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 0.0)); 4];
+    for i in 0..4 {
+        let (position_a, direction_a) = corners[i];
+        let (position_b, direction_b) = corners[(i + 1) % 4];
+        let mut normal = direction_a
+            .cross(position_b + direction_b - position_a)
+            .normalize();
+        if normal.dot(center - position_a) < 0.0 {
+            normal = -normal;
+        }
+        planes[i] = (normal, position_a);
+    }
+    planes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_line_number_mesa() {
+        assert_eq!(
+            log_line_number("0:12(5): error: `foo' undeclared"),
+            Some(12)
+        );
+        assert_eq!(
+            log_line_number("0:7(22): error: no function with name 'bar'"),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn log_line_number_angle() {
+        assert_eq!(
+            log_line_number("ERROR: 0:12: 'foo' : undeclared identifier"),
+            Some(12)
+        );
+        assert_eq!(
+            log_line_number(
+                "ERROR: 0:3: 'bar' : no matching overloaded function found (using implicit conversion)"
+            ),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn log_line_number_nvidia() {
+        assert_eq!(
+            log_line_number("0(12) : error C1008: undefined variable \"foo\""),
+            Some(12)
+        );
+        assert_eq!(
+            log_line_number("0(4) : error C0000: syntax error, unexpected '(', expecting ';'"),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn log_line_number_without_location() {
+        assert_eq!(log_line_number("ERROR: 1 compilation errors."), None);
+        assert_eq!(log_line_number(""), None);
+    }
+
+    #[test]
+    fn source_line_number_inside_fragment() {
+        let source = "#version 300 es\nprecision highp float;\nvoid f() {}\nvoid main() {\n}\n";
+        let fragment = "void main() {\n}\n";
+        assert_eq!(source_line_number(source, fragment, 4), Some(1));
+        assert_eq!(source_line_number(source, fragment, 5), Some(2));
+    }
+
+    #[test]
+    fn source_line_number_outside_fragment() {
+        let source = "#version 300 es\nprecision highp float;\nvoid f() {}\nvoid main() {\n}\n";
+        let fragment = "void f() {}\n";
+        assert_eq!(source_line_number(source, fragment, 2), None);
+        assert_eq!(source_line_number(source, fragment, 4), None);
+        assert_eq!(source_line_number(source, "", 1), None);
+        assert_eq!(source_line_number(source, "void g() {}", 1), None);
+    }
+
+    #[test]
+    fn source_line_number_from_log() {
+        let source = "#version 330 core\nuniform vec3 eye;\nvoid main() {\n    foo = 1.0;\n}\n";
+        let fragment = "void main() {\n    foo = 1.0;\n}\n";
+        for line in [
+            "0:4(5): error: `foo' undeclared",
+            "ERROR: 0:4: 'foo' : undeclared identifier",
+            "0(4) : error C1008: undefined variable \"foo\"",
+        ] {
+            assert_eq!(
+                log_line_number(line).and_then(|line_number| source_line_number(
+                    source,
+                    fragment,
+                    line_number
+                )),
+                Some(2)
+            );
+        }
+    }
+}
@@ -109,6 +109,46 @@ impl Camera {
         self.tone_mapping = ToneMapping::default();
         self.color_mapping = ColorMapping::default();
     }
+
+    ///
+    /// Returns the shader source declaring the `Camera` uniform block which contains the camera data sent by [Camera::use_uniform_block].
+    /// The block contains the following variables:
+    /// ```no_rust
+    /// mat4 cameraViewProjection;
+    /// mat4 cameraView;
+    /// mat4 cameraProjection;
+    /// vec3 cameraEye;
+    /// ```
+    ///
+    pub fn uniform_block_source() -> &'static str {
+        "
+        layout (std140) uniform Camera
+        {
+            mat4 cameraViewProjection;
+            mat4 cameraView;
+            mat4 cameraProjection;
+            vec3 cameraEye;
+        };
+        "
+    }
+
+    ///
+    /// Sends the data of this camera to the `Camera` uniform block declared by [Camera::uniform_block_source].
+    /// The data is stored in a uniform buffer shared between all programs (see [Program::use_shared_uniform_block]),
+    /// so it is only sent to the GPU when the camera changes and not for each draw call.
+    ///
+    pub fn use_uniform_block(&self, program: &Program) {
+        let view = *self.view();
+        let projection = *self.projection();
+        let mut data = Vec::with_capacity(52);
+        for matrix in [projection * view, view, projection] {
+            let matrix: &[f32; 16] = matrix.as_ref();
+            data.extend_from_slice(matrix);
+        }
+        let position = self.position();
+        data.extend_from_slice(&[position.x, position.y, position.z, 0.0]);
+        program.use_shared_uniform_block("Camera", &data);
+    }
 }
 
 use std::ops::Deref;
@@ -26,6 +26,14 @@ mod bev_control;
 #[doc(inline)]
 pub use bev_control::*;
 
+mod camera_animator;
+#[doc(inline)]
+pub use camera_animator::*;
+
+mod trackball_control;
+#[doc(inline)]
+pub use trackball_control::*;
+
 pub use three_d_asset::PixelPoint as PhysicalPoint;
 
 /// Type of mouse button.
@@ -39,6 +47,19 @@ pub enum MouseButton {
     Middle,
 }
 
+/// The phase of a touch, see [Event::Touch].
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+pub enum TouchPhase {
+    /// A finger started touching the screen.
+    Started,
+    /// A finger on the screen moved.
+    Moved,
+    /// A finger stopped touching the screen.
+    Ended,
+    /// The touch was cancelled, for example because the window lost focus.
+    Cancelled,
+}
+
 /// An input event (from mouse, keyboard or similar).
 #[derive(Clone, Debug)]
 pub enum Event {
@@ -92,6 +113,56 @@ pub enum Event {
     MouseEnter,
     /// Fired when the mouse leaves the window.
     MouseLeave,
+    /// Fired for each finger on the screen when it starts touching, moves or stops touching the screen.
+    /// The first finger touching the screen also generates mouse events with the left button,
+    /// and gestures with two fingers also generate [Event::Pinch], [Event::TwoFingerPan] and [Event::Rotate] events.
+    Touch {
+        /// An identifier of the finger, which is unique while the finger is touching the screen.
+        id: u64,
+        /// The phase of the touch.
+        phase: TouchPhase,
+        /// The screen position in physical pixels.
+        position: PhysicalPoint,
+        /// The state of modifiers.
+        modifiers: Modifiers,
+        /// Whether or not this event already have been handled.
+        handled: bool,
+    },
+    /// Fired continuously when the distance between two fingers on the screen changes.
+    Pinch {
+        /// The change in distance between the two fingers since the last [Event::Pinch] event in logical pixels.
+        /// Positive when the fingers move apart and negative when they move towards each other.
+        delta: f32,
+        /// The screen position in physical pixels of the point between the two fingers.
+        position: PhysicalPoint,
+        /// The state of modifiers.
+        modifiers: Modifiers,
+        /// Whether or not this event already have been handled.
+        handled: bool,
+    },
+    /// Fired continuously when two fingers on the screen are moved in the same direction.
+    TwoFingerPan {
+        /// The relative movement of the point between the two fingers since the last [Event::TwoFingerPan] event in logical pixels.
+        delta: (f32, f32),
+        /// The screen position in physical pixels of the point between the two fingers.
+        position: PhysicalPoint,
+        /// The state of modifiers.
+        modifiers: Modifiers,
+        /// Whether or not this event already have been handled.
+        handled: bool,
+    },
+    /// Fired continuously when two fingers on the screen are rotated around each other.
+    Rotate {
+        /// The rotation in radians since the last [Event::Rotate] event.
+        /// Positive when the fingers rotate counter-clockwise on the screen.
+        angle: f32,
+        /// The screen position in physical pixels of the point between the two fingers.
+        position: PhysicalPoint,
+        /// The state of modifiers.
+        modifiers: Modifiers,
+        /// Whether or not this event already have been handled.
+        handled: bool,
+    },
     /// Fired when a key is pressed.
     KeyPress {
         /// The type of key.
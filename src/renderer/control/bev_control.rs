@@ -1,144 +1,394 @@
 use crate::renderer::*;
-use three_d_asset::{PixelPoint, ProjectionType};
+#[cfg(target_arch = "wasm32")]
+use instant::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use three_d_asset::ProjectionType;
 
 ///
 /// MapControls is intended for bird's eye perspective.
-/// 1. 鼠标右键+移动 = 场景移动
-/// 2. 鼠标滚轮 = 缩放
+/// The ground is the plane with normal in the positive z direction at the height [BevControl::ground_height].
+/// 1. 鼠标左键+移动 = 场景移动, 鼠标下的地面点保持在鼠标下 (松开后带惯性)
+/// 2. 鼠标右键+移动 = 水平移动旋转场景, 垂直移动倾斜相机
+/// 3. 鼠标滚轮 = 以鼠标点为中心缩放
+/// 4. 双指拖动 = 场景移动, 双指缩放 = 缩放, 双指旋转 = 旋转
+///
+/// Works with both orthographic and perspective cameras.
+/// For a perspective camera, the zoom changes the distance from the camera to the ground point in the center of the view
+/// and for an orthographic camera, the zoom changes the height of the view.
 ///
 pub struct BevControl {
     min_distance: f32,
     max_distance: f32,
-    ratio: f32, // 一个像素代表的物理单位 的比例
+    z_planes: Option<(f32, f32)>,
+    /// The height of the ground plane.
+    pub ground_height: f32,
+    /// The minimum angle between the view direction and straight down when tilting the camera.
+    pub min_tilt: Radians,
+    /// The maximum angle between the view direction and straight down when tilting the camera.
+    pub max_tilt: Radians,
+    /// The speed of rotating and tilting the camera in radians per logical pixel.
+    pub rotation_speed: f32,
+    /// Whether or not the scene keeps moving after the left mouse button is released while panning.
+    pub kinetic_panning: bool,
+    /// How quickly the kinetic panning slows down.
+    /// The velocity is multiplied by `exp(-damping * t)` where `t` is the time in seconds since the release.
+    pub damping: f32,
+    /// If specified, the ground point in the center of the view is kept inside these bounds (the z coordinate is ignored).
+    pub bounds: Option<AxisAlignedBoundingBox>,
+    grab: Option<Vec3>,
+    regrab: bool,
+    pan_position: Option<PhysicalPoint>,
+    velocity: Vec3,
+    last_motion: Option<Instant>,
+    last_update: Instant,
 }
 
 impl BevControl {
-    /// Creates a new orbit control with the given target and minimum and maximum distance to the target.
-    pub fn new(min_distance: f32, max_distance: f32) -> Self {
+    ///
+    /// Creates a new bird's eye view control which sets the given near and far planes of an orthographic camera when zooming, as in earlier versions of this control.
+    /// The zoom is not limited, use [Self::with_zoom_limits] to create a control with a minimum and maximum zoom instead.
+    ///
+    pub fn new(z_near: f32, z_far: f32) -> Self {
+        Self {
+            z_planes: Some((z_near, z_far)),
+            ..Self::with_zoom_limits(0.0, f32::MAX)
+        }
+    }
+
+    ///
+    /// Creates a new bird's eye view control with the given minimum and maximum distance to the ground point in the center of the view.
+    /// For an orthographic camera, the minimum and maximum distance is instead the minimum and maximum height of the view.
+    /// The near and far planes of the camera are not changed.
+    ///
+    pub fn with_zoom_limits(min_distance: f32, max_distance: f32) -> Self {
         Self {
             min_distance,
             max_distance,
-            ratio: 0.0,
+            z_planes: None,
+            ground_height: 0.0,
+            min_tilt: radians(0.0),
+            max_tilt: degrees(60.0).into(),
+            rotation_speed: 0.005,
+            kinetic_panning: true,
+            damping: 5.0,
+            bounds: None,
+            grab: None,
+            regrab: false,
+            pan_position: None,
+            velocity: vec3(0.0, 0.0, 0.0),
+            last_motion: None,
+            last_update: Instant::now(),
         }
     }
 
     /// Handles the events. Must be called each frame.
     pub fn handle_events(&mut self, camera: &mut Camera, events: &mut [Event]) -> bool {
-        for event in events {
-            self.update_self(camera);
+        let mut change = self.update_kinetic_panning(camera);
+        for event in events.iter_mut() {
             match event {
+                Event::MousePress {
+                    button,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if !*handled && *button == MouseButton::Left {
+                        // 抓住鼠标下的地面点
+                        self.grab = self.ground_point(camera, *position);
+                        self.regrab = false;
+                        self.velocity = vec3(0.0, 0.0, 0.0);
+                        self.last_motion = None;
+                        *handled = self.grab.is_some();
+                    }
+                }
+                Event::MouseRelease {
+                    button, handled, ..
+                } => {
+                    if *button == MouseButton::Left && self.grab.is_some() {
+                        self.grab = None;
+                        // 只有在松开前还在移动时才保留惯性
+                        let moving = self
+                            .last_motion
+                            .map(|t| t.elapsed().as_secs_f32() < 0.1)
+                            .unwrap_or(false);
+                        if !self.kinetic_panning || !moving {
+                            self.velocity = vec3(0.0, 0.0, 0.0);
+                        }
+                        *handled = true;
+                    }
+                }
                 Event::MouseMotion {
                     button,
                     delta,
-                    position: _position,
-                    modifiers: _modifiers,
-                    handled: _handled,
+                    position,
+                    handled,
+                    ..
                 } => {
-                    if let Some(button) = button {
-                        match button {
-                            MouseButton::Left => {
-                                // todo
-                            }
-                            MouseButton::Right => {
-                                // 鼠标右键 滑动视窗
-                                self.move_(camera, -delta.0 * self.ratio, delta.1 * self.ratio);
+                    if *handled {
+                        continue;
+                    }
+                    match button {
+                        Some(MouseButton::Left) => {
+                            if let Some(grab) = self.grab {
+                                if self.regrab {
+                                    self.grab = self.ground_point(camera, *position);
+                                    self.regrab = false;
+                                } else if let Some(point) = self.ground_point(camera, *position) {
+                                    let translation = grab - point;
+                                    camera.translate(&translation);
+                                    self.update_velocity(translation);
+                                    self.clamp_to_bounds(camera);
+                                    change = true;
+                                }
+                                *handled = true;
                             }
-                            MouseButton::Middle => {}
                         }
+                        Some(MouseButton::Right) => {
+                            let pivot = self.pivot(camera);
+                            self.rotate(camera, pivot, -delta.0 * self.rotation_speed);
+                            self.tilt(camera, delta.1 * self.rotation_speed);
+                            *handled = true;
+                            change = true;
+                        }
+                        _ => {}
                     }
                 }
                 Event::MouseWheel {
                     delta,
-                    position, // 左下角是原点
-                    modifiers: _modifiers,
-                    handled: _handled,
+                    position,
+                    handled,
+                    ..
                 } => {
-                    // println!("MouseWheel {:?}", delta);
-                    // 经过缩放后. 单个像素表示物理单位,  scale变小 表示在显示上要放大元素
-                    let scale = if delta.1 > 0.0 { 0.8 } else { 1.2 };
-                    self.zoom_(camera, position, scale);
+                    if !*handled {
+                        // 滚轮向上 = 放大
+                        let scale = (-0.005 * delta.1).exp();
+                        self.zoom_(camera, *position, scale);
+                        *handled = true;
+                        change = true;
+                    }
+                }
+                Event::Touch { phase, .. } => {
+                    // 手指数量变化时重新抓取地面点, 避免场景跳动
+                    if *phase != TouchPhase::Moved {
+                        self.regrab = true;
+                        self.pan_position = None;
+                    }
+                }
+                Event::Pinch {
+                    delta,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        // 双指张开 = 放大, 双指合拢 = 缩小
+                        let scale = (-0.005 * *delta).exp();
+                        self.zoom_(camera, *position, scale);
+                        *handled = true;
+                        change = true;
+                    }
+                }
+                Event::TwoFingerPan {
+                    position, handled, ..
+                } => {
+                    if !*handled {
+                        // 双指拖动 滑动视窗, 两指中点下的地面点保持在中点下
+                        if let Some(last) = self.pan_position {
+                            if let (Some(from), Some(to)) = (
+                                self.ground_point(camera, last),
+                                self.ground_point(camera, *position),
+                            ) {
+                                camera.translate(&(from - to));
+                                self.clamp_to_bounds(camera);
+                                change = true;
+                            }
+                        }
+                        self.pan_position = Some(*position);
+                        *handled = true;
+                    }
+                }
+                Event::Rotate {
+                    angle,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        // 双指旋转 绕两指中点下的地面点旋转
+                        let pivot = self
+                            .ground_point(camera, *position)
+                            .unwrap_or_else(|| self.pivot(camera));
+                        self.rotate(camera, pivot, -*angle);
+                        *handled = true;
+                        change = true;
+                    }
                 }
-                Event::KeyPress {
-                    kind,
-                    modifiers: _modifiers,
-                    handled: _handled,
-                } => match kind {
-                    Key::ArrowDown => {}
-                    Key::ArrowLeft => {}
-                    Key::ArrowRight => {}
-                    Key::ArrowUp => {}
-                    Key::A => {}
-                    Key::D => {}
-                    Key::S => {}
-                    Key::W => {}
-                    _ => {}
-                },
                 _ => {}
             }
         }
+        change
+    }
+
+    /// 松开鼠标后按速度继续移动场景, 速度按指数衰减
+    fn update_kinetic_panning(&mut self, camera: &mut Camera) -> bool {
+        let now = Instant::now();
+        let elapsed_time = now.duration_since(self.last_update).as_secs_f32() * 1000.0;
+        self.last_update = now;
+        if self.grab.is_some() || self.velocity.magnitude2() == 0.0 {
+            return false;
+        }
+        camera.translate(&(self.velocity * elapsed_time));
+        self.velocity *= (-self.damping * elapsed_time * 0.001).exp();
+        let pixel_size = self.pixel_size(camera);
+        if self.velocity.magnitude() * 16.0 < 0.1 * pixel_size {
+            self.velocity = vec3(0.0, 0.0, 0.0);
+        }
+        self.clamp_to_bounds(camera);
+        true
+    }
+
+    /// 根据拖动的位移计算速度 (每毫秒的物理单位)
+    fn update_velocity(&mut self, translation: Vec3) {
+        let now = Instant::now();
+        if let Some(last_motion) = self.last_motion {
+            let elapsed_time = now.duration_since(last_motion).as_secs_f32() * 1000.0;
+            if elapsed_time > 0.0 {
+                self.velocity = self.velocity * 0.2 + translation / elapsed_time * 0.8;
+            }
+        }
+        self.last_motion = Some(now);
+    }
+
+    /// 屏幕点对应的射线与地面的交点
+    fn ground_point(&self, camera: &Camera, pixel: PhysicalPoint) -> Option<Vec3> {
+        let position = camera.position_at_pixel(pixel);
+        let direction = camera.view_direction_at_pixel(pixel);
+        if direction.z.abs() < 0.0001 {
+            return None;
+        }
+        let t = (self.ground_height - position.z) / direction.z;
+        if t < 0.0 {
+            return None;
+        }
+        Some(position + direction * t)
+    }
 
-        return true;
+    /// 视窗中心下的地面点, 相机围绕这个点旋转和倾斜
+    fn pivot(&self, camera: &Camera) -> Vec3 {
+        let viewport = camera.viewport();
+        let center = PhysicalPoint {
+            x: viewport.x as f32 + 0.5 * viewport.width as f32,
+            y: viewport.y as f32 + 0.5 * viewport.height as f32,
+        };
+        self.ground_point(camera, center)
+            .unwrap_or_else(|| *camera.target())
     }
-    fn update_self(&mut self, camera: &mut Camera) {
-        let c_type = camera.projection_type().clone();
-        let physic_height = match c_type {
-            // 相机视图里代表的物理真实高度
+
+    /// 视窗中心处一个像素代表的物理单位
+    fn pixel_size(&self, camera: &Camera) -> f32 {
+        let height = match camera.projection_type().clone() {
             ProjectionType::Orthographic { height } => height,
-            ProjectionType::Perspective { .. } => {
-                return;
+            ProjectionType::Perspective { field_of_view_y } => {
+                let distance = camera.position().distance(self.pivot(camera));
+                2.0 * distance * (0.5 * field_of_view_y.0).tan()
             }
         };
-        self.ratio = physic_height / camera.viewport().height as f32; // 像素单位对应的真实物理单位
+        height / camera.viewport().height.max(1) as f32
     }
 
-    /// 从bev视角上移动相机
-    fn move_(&mut self, camera: &mut Camera, move_x: f32, move_y: f32) {
-        // 鼠标右键 滑动视窗
-        let new_position = Vec3::new(
-            camera.position().x + move_x,
-            camera.position().y + move_y,
-            camera.position().z,
-        );
-        let new_target = Vec3::new(
-            camera.target().x + move_x,
-            camera.target().y + move_y,
-            camera.target().z,
-        );
-        let up = camera.up().clone();
-        camera.set_view(new_position, new_target, up);
+    /// 水平方向的朝向和相机的倾斜角
+    fn heading_and_tilt(camera: &Camera) -> (Vec3, f32) {
+        let forward = camera.view_direction();
+        let up = camera.up().normalize();
+        let heading = vec3(forward.x + up.x, forward.y + up.y, 0.0);
+        let heading = if heading.magnitude2() > 0.000001 {
+            heading.normalize()
+        } else {
+            vec3(0.0, 1.0, 0.0)
+        };
+        let tilt = (-forward.z).clamp(-1.0, 1.0).acos();
+        (heading, tilt)
+    }
+
+    /// 在给定的朝向, 倾斜角和距离下从上方看向pivot
+    fn look_at(camera: &mut Camera, pivot: Vec3, heading: Vec3, tilt: f32, distance: f32) {
+        let z = vec3(0.0, 0.0, 1.0);
+        let (sin, cos) = tilt.sin_cos();
+        let position = pivot + (z * cos - heading * sin) * distance;
+        let up = heading * cos + z * sin;
+        camera.set_view(position, pivot, up);
     }
 
-    /// 从bev视角上缩放 相机, 以当前鼠标点为中心缩放
-    fn zoom_(&mut self, camera: &mut Camera, position: &mut PixelPoint, scale: f32) {
-        // 经过缩放后. 单个像素表示物理单位,  scale变小 表示在显示上要放大元素
-        let new_ratio = self.ratio * scale;
-        let (delta_screen_x, delta_screen_y) = (
-            camera.viewport().width as f32 / 2.0 - position.x,
-            camera.viewport().height as f32 / 2.0 - position.y,
-        ); // UI中心点的y和鼠标点的y的差值
-        let (delta_x, delta_y) = (
-            delta_screen_x * (new_ratio - self.ratio),
-            delta_screen_y * (new_ratio - self.ratio),
-        ); // 对应到物理世界的 x, y的差值
+    /// 绕过pivot的竖直轴旋转相机
+    fn rotate(&mut self, camera: &mut Camera, pivot: Vec3, angle: f32) {
+        let rotation = Mat3::from_angle_z(radians(angle));
+        let position = pivot + rotation * (*camera.position() - pivot);
+        let target = pivot + rotation * (*camera.target() - pivot);
+        let up = rotation * *camera.up();
+        camera.set_view(position, target, up);
+        self.velocity = rotation * self.velocity;
+    }
 
-        let new_position = Vec3::new(
-            camera.position().x + delta_x,
-            camera.position().y + delta_y,
-            camera.position().z,
-        );
-        let new_target = Vec3::new(
-            camera.target().x + delta_x,
-            camera.target().y + delta_y,
-            camera.target().z,
-        );
-        let up = camera.up().clone();
+    /// 倾斜相机, 倾斜角限制在 min_tilt 和 max_tilt 之间
+    fn tilt(&mut self, camera: &mut Camera, angle: f32) {
+        let pivot = self.pivot(camera);
+        let distance = camera.position().distance(pivot);
+        let (heading, tilt) = Self::heading_and_tilt(camera);
+        let tilt = (tilt + angle).clamp(self.min_tilt.0, self.max_tilt.0.max(self.min_tilt.0));
+        Self::look_at(camera, pivot, heading, tilt, distance);
+    }
 
-        camera.set_view(new_position, new_target, up);
-        let physic_height = self.ratio * camera.viewport().height as f32;
-        camera.set_orthographic_projection(
-            physic_height * scale,
-            self.min_distance,
-            self.max_distance,
-        );
+    /// 以鼠标点为中心缩放相机, 鼠标下的地面点保持在鼠标下
+    fn zoom_(&mut self, camera: &mut Camera, position: PhysicalPoint, scale: f32) {
+        let projection_type = camera.projection_type().clone();
+        match projection_type {
+            ProjectionType::Orthographic { height } => {
+                let new_height = (height * scale).clamp(self.min_distance, self.max_distance);
+                let scale = new_height / height;
+                let offset = camera.position_at_pixel(position) - *camera.position();
+                camera.translate(&(offset * (1.0 - scale)));
+                let (z_near, z_far) = self
+                    .z_planes
+                    .unwrap_or_else(|| (camera.z_near(), camera.z_far()));
+                camera.set_orthographic_projection(new_height, z_near, z_far);
+            }
+            ProjectionType::Perspective { .. } => {
+                let distance = camera.position().distance(self.pivot(camera));
+                let new_distance = (distance * scale).clamp(self.min_distance, self.max_distance);
+                let scale = new_distance / distance;
+                // 鼠标下没有地面点时(例如指向天空), 以视窗中心下的地面点为中心缩放
+                let point = self
+                    .ground_point(camera, position)
+                    .unwrap_or_else(|| self.pivot(camera));
+                let new_position = point + (*camera.position() - point) * scale;
+                let new_target = point + (*camera.target() - point) * scale;
+                let up = *camera.up();
+                camera.set_view(new_position, new_target, up);
+            }
+        }
+        self.clamp_to_bounds(camera);
+    }
+
+    /// 把视窗中心下的地面点限制在边界内
+    fn clamp_to_bounds(&mut self, camera: &mut Camera) {
+        if let Some(bounds) = self.bounds {
+            let pivot = self.pivot(camera);
+            let (min, max) = (bounds.min(), bounds.max());
+            let clamped = vec3(
+                pivot.x.clamp(min.x, max.x.max(min.x)),
+                pivot.y.clamp(min.y, max.y.max(min.y)),
+                pivot.z,
+            );
+            if clamped != pivot {
+                camera.translate(&(clamped - pivot));
+                if clamped.x != pivot.x {
+                    self.velocity.x = 0.0;
+                }
+                if clamped.y != pivot.y {
+                    self.velocity.y = 0.0;
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,286 @@
+use crate::renderer::*;
+use three_d_asset::ProjectionType;
+
+///
+/// An easing curve which maps the linear progress of a [CameraAnimator] transition in the range `[0, 1]` to the interpolation factor in the range `[0, 1]`.
+///
+#[derive(Clone, Copy, Debug)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slow and accelerates (cubic).
+    EaseIn,
+    /// Starts fast and decelerates (cubic).
+    EaseOut,
+    /// Starts slow, accelerates and then decelerates at the end (cubic).
+    EaseInOut,
+    /// A custom easing curve which should map 0 to 0 and 1 to 1.
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    ///
+    /// Returns the interpolation factor for the given progress in the range `[0, 1]`.
+    ///
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - 0.5 * (2.0 - 2.0 * t).powi(3)
+                }
+            }
+            Self::Custom(f) => f(t),
+        }
+    }
+}
+
+impl std::default::Default for Easing {
+    fn default() -> Self {
+        Self::EaseInOut
+    }
+}
+
+///
+/// Animates smooth transitions of a [Camera] between two camera states.
+/// The position, target, up direction and field of view (or height for an orthographic camera) are interpolated using an [Easing] curve over a duration.
+///
+/// Call [CameraAnimator::handle_events] each frame before the events are handled by a control, for example [OrbitControl].
+/// If the transition is [interruptible](CameraAnimator::interruptible), user input stops the transition
+/// and the control takes over from the current camera state, otherwise the input events are marked as handled until the transition is done.
+///
+#[derive(Clone, Debug)]
+pub struct CameraAnimator {
+    /// Whether or not user input stops a transition in flight.
+    pub interruptible: bool,
+    transition: Option<Transition>,
+}
+
+#[derive(Clone, Debug)]
+struct Transition {
+    from: Camera,
+    to: Camera,
+    duration: f64,
+    elapsed_time: f64,
+    easing: Easing,
+}
+
+impl CameraAnimator {
+    ///
+    /// Creates a new camera animator without any transition.
+    ///
+    pub fn new() -> Self {
+        Self {
+            interruptible: true,
+            transition: None,
+        }
+    }
+
+    ///
+    /// Starts a transition from the current state of the given camera to the state of the `to` camera over the given duration in milliseconds.
+    /// Only the view and projection of the `to` camera is used, ie. the viewport, tone mapping and color mapping of the animated camera is kept.
+    /// If the projection types of the two cameras are different, the projection is changed at the end of the transition.
+    ///
+    pub fn animate_to(&mut self, camera: &Camera, to: Camera, duration: f64, easing: Easing) {
+        self.transition = Some(Transition {
+            from: camera.clone(),
+            to,
+            duration,
+            elapsed_time: 0.0,
+            easing,
+        });
+    }
+
+    ///
+    /// Starts a transition which frames the given bounding box, ie. moves the camera so that the bounding box is centered and fills the view.
+    /// The view direction and up direction of the camera is kept.
+    /// Returns the new target of the camera, which for example can be given to [OrbitControl::set_target], or `None` if the bounding box is empty.
+    ///
+    pub fn fly_to_aabb(
+        &mut self,
+        camera: &Camera,
+        aabb: AxisAlignedBoundingBox,
+        duration: f64,
+        easing: Easing,
+    ) -> Option<Vec3> {
+        if aabb.is_empty() {
+            return None;
+        }
+        let center = aabb.center();
+        let radius = (0.5 * aabb.size().magnitude()).max(0.0001);
+        let viewport = camera.viewport();
+        let aspect = viewport.width as f32 / viewport.height.max(1) as f32;
+        let direction = camera.view_direction();
+        let up = *camera.up();
+
+        let mut to = camera.clone();
+        match camera.projection_type().clone() {
+            ProjectionType::Perspective { field_of_view_y } => {
+                let half_y = 0.5 * field_of_view_y.0;
+                let half_x = (half_y.tan() * aspect).atan();
+                let distance = radius / half_y.min(half_x).sin();
+                to.set_view(center - direction * distance, center, up);
+            }
+            ProjectionType::Orthographic { .. } => {
+                let distance = camera
+                    .position()
+                    .distance(*camera.target())
+                    .max(radius + camera.z_near());
+                to.set_view(center - direction * distance, center, up);
+                let height = 2.0 * radius * (1.0 / aspect).max(1.0);
+                let (z_near, z_far) = (camera.z_near(), camera.z_far());
+                to.set_orthographic_projection(height, z_near, z_far);
+            }
+        }
+        self.animate_to(camera, to, duration, easing);
+        Some(center)
+    }
+
+    ///
+    /// Returns whether or not a transition is in flight.
+    ///
+    pub fn is_animating(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    ///
+    /// Stops the transition in flight, if any, leaving the camera in its current state.
+    ///
+    pub fn stop(&mut self) {
+        self.transition = None;
+    }
+
+    ///
+    /// Advances the transition in flight by the given elapsed time in milliseconds (for example the `elapsed_time` of the frame input) and updates the camera.
+    /// Returns whether or not the camera changed.
+    ///
+    pub fn update(&mut self, camera: &mut Camera, elapsed_time: f64) -> bool {
+        if let Some(transition) = &mut self.transition {
+            transition.elapsed_time += elapsed_time;
+            let progress = if transition.duration > 0.0 {
+                (transition.elapsed_time / transition.duration) as f32
+            } else {
+                1.0
+            };
+            transition.apply(camera, progress);
+            if progress >= 1.0 {
+                self.transition = None;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// Handles the events and advances the transition in flight. Must be called each frame before the events are handled by a control.
+    /// If [CameraAnimator::interruptible] is true, unhandled user input stops the transition,
+    /// otherwise the user input is marked as handled while the transition is in flight.
+    /// Returns whether or not the camera changed.
+    ///
+    pub fn handle_events(
+        &mut self,
+        camera: &mut Camera,
+        events: &mut [Event],
+        elapsed_time: f64,
+    ) -> bool {
+        if self.transition.is_none() {
+            return false;
+        }
+        for event in events.iter_mut() {
+            if let Some(handled) = user_input_handled(event) {
+                if !*handled {
+                    if self.interruptible {
+                        self.stop();
+                        return false;
+                    }
+                    *handled = true;
+                }
+            }
+        }
+        self.update(camera, elapsed_time)
+    }
+}
+
+impl std::default::Default for CameraAnimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transition {
+    fn apply(&self, camera: &mut Camera, progress: f32) {
+        let t = self.easing.apply(progress);
+        let position = self.from.position() + (self.to.position() - self.from.position()) * t;
+        let target = self.from.target() + (self.to.target() - self.from.target()) * t;
+        let up = self.from.up() + (self.to.up() - self.from.up()) * t;
+        let up = if up.magnitude2() > 0.000001 {
+            up.normalize()
+        } else {
+            *self.to.up()
+        };
+        camera.set_view(position, target, up);
+
+        let z_near = self.from.z_near() + (self.to.z_near() - self.from.z_near()) * t;
+        let z_far = self.from.z_far() + (self.to.z_far() - self.from.z_far()) * t;
+        match (
+            self.from.projection_type().clone(),
+            self.to.projection_type().clone(),
+        ) {
+            (
+                ProjectionType::Perspective {
+                    field_of_view_y: from,
+                },
+                ProjectionType::Perspective {
+                    field_of_view_y: to,
+                },
+            ) => {
+                camera.set_perspective_projection(from + (to - from) * t, z_near, z_far);
+            }
+            (
+                ProjectionType::Orthographic { height: from },
+                ProjectionType::Orthographic { height: to },
+            ) => {
+                // Interpolate the height exponentially so the zoom speed appears constant
+                camera.set_orthographic_projection(
+                    from * (to / from.max(0.0001)).powf(t),
+                    z_near,
+                    z_far,
+                );
+            }
+            (_, to) if progress >= 1.0 => match to {
+                ProjectionType::Perspective { field_of_view_y } => {
+                    camera.set_perspective_projection(field_of_view_y, z_near, z_far);
+                }
+                ProjectionType::Orthographic { height } => {
+                    camera.set_orthographic_projection(height, z_near, z_far);
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Returns the handled flag of the events which are input from the user.
+fn user_input_handled(event: &mut Event) -> Option<&mut bool> {
+    match event {
+        Event::MousePress { handled, .. }
+        | Event::MouseWheel { handled, .. }
+        | Event::KeyPress { handled, .. }
+        | Event::Touch { handled, .. }
+        | Event::Pinch { handled, .. }
+        | Event::TwoFingerPan { handled, .. }
+        | Event::Rotate { handled, .. } => Some(handled),
+        Event::MouseMotion {
+            button: Some(_),
+            handled,
+            ..
+        } => Some(handled),
+        _ => None,
+    }
+}
@@ -62,6 +62,16 @@ pub enum CameraAction {
         /// The maximum distance to the target.
         max: f32,
     },
+    /// Rotates the camera around the given target as if dragging a virtual sphere (trackball) centered in the viewport,
+    /// ie. the point on the sphere under the cursor follows the cursor.
+    /// The rotation does not keep a fixed up direction, so it does not gimbal at the poles and allows free roll when dragging near the edge of the sphere.
+    /// Only applies when dragging, then both horizontally and vertically.
+    Arcball {
+        /// The target of the rotation.
+        target: Vec3,
+        /// The speed of the rotation, where 1.0 means that the point under the cursor follows the cursor exactly.
+        speed: f32,
+    },
 }
 
 impl std::default::Default for CameraAction {
@@ -70,11 +80,167 @@ impl std::default::Default for CameraAction {
     }
 }
 
+///
+/// An input which can be bound to a [CameraAction] in [CameraBindings].
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum CameraInput {
+    /// Dragging horizontally with the given mouse button.
+    DragHorizontal(MouseButton),
+    /// Dragging vertically with the given mouse button.
+    DragVertical(MouseButton),
+    /// Scrolling horizontally.
+    ScrollHorizontal,
+    /// Scrolling vertically.
+    ScrollVertical,
+    /// Holding down the given key.
+    /// The action is applied each frame with the elapsed time in milliseconds as the amount,
+    /// so the speed of the action is per millisecond.
+    Key(Key),
+    /// Pinching with two fingers.
+    Pinch,
+    /// Panning horizontally with two fingers.
+    TwoFingerPanHorizontal,
+    /// Panning vertically with two fingers.
+    TwoFingerPanVertical,
+    /// Rotating two fingers around each other.
+    Rotate,
+}
+
+///
+/// A binding of a [CameraInput] together with a state of the modifiers to a [CameraAction].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraBinding {
+    /// The input.
+    pub input: CameraInput,
+    /// The modifiers which must be down when the input is applied.
+    /// The alt and shift modifiers must match exactly. The ctrl and command modifiers which are set must be down
+    /// and if neither of them are set, neither ctrl nor command must be down.
+    pub modifiers: Modifiers,
+    /// The action to apply to the camera.
+    pub action: CameraAction,
+}
+
+impl CameraBinding {
+    fn matches(&self, input: CameraInput, modifiers: Modifiers) -> bool {
+        let m = self.modifiers;
+        self.input == input
+            && m.alt == modifiers.alt
+            && m.shift == modifiers.shift
+            && (m.ctrl || m.command) == (modifiers.ctrl || modifiers.command)
+            && (!m.ctrl || modifiers.ctrl)
+            && (!m.command || modifiers.command)
+    }
+}
+
+///
+/// A table of [CameraBinding]s used by [CameraControl], for example
+/// ```no_run
+/// # use three_d::*;
+/// let mut control = CameraControl::new();
+/// let shift = Modifiers {
+///     shift: true,
+///     ..Default::default()
+/// };
+/// // Shift + left drag pans the camera
+/// control.bindings.bind(CameraInput::DragHorizontal(MouseButton::Left), shift, CameraAction::Left { speed: 0.01 });
+/// control.bindings.bind(CameraInput::DragVertical(MouseButton::Left), shift, CameraAction::Up { speed: 0.01 });
+/// // WASD moves the camera and Q/E rolls the camera
+/// let none = Modifiers::default();
+/// control.bindings.bind(CameraInput::Key(Key::W), none, CameraAction::Forward { speed: 0.01 });
+/// control.bindings.bind(CameraInput::Key(Key::S), none, CameraAction::Forward { speed: -0.01 });
+/// control.bindings.bind(CameraInput::Key(Key::A), none, CameraAction::Left { speed: 0.01 });
+/// control.bindings.bind(CameraInput::Key(Key::D), none, CameraAction::Left { speed: -0.01 });
+/// control.bindings.bind(CameraInput::Key(Key::Q), none, CameraAction::Roll { speed: -0.001 });
+/// control.bindings.bind(CameraInput::Key(Key::E), none, CameraAction::Roll { speed: 0.001 });
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct CameraBindings {
+    bindings: Vec<CameraBinding>,
+}
+
+impl CameraBindings {
+    ///
+    /// Creates a new empty binding table.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Binds the given input together with the given modifiers to the given action.
+    /// Replaces the existing binding for the input and modifiers, if any.
+    ///
+    pub fn bind(&mut self, input: CameraInput, modifiers: Modifiers, action: CameraAction) {
+        self.unbind(input, modifiers);
+        self.bindings.push(CameraBinding {
+            input,
+            modifiers,
+            action,
+        });
+    }
+
+    ///
+    /// Removes the binding for the given input and modifiers, if any.
+    ///
+    pub fn unbind(&mut self, input: CameraInput, modifiers: Modifiers) {
+        self.bindings
+            .retain(|b| b.input != input || b.modifiers != modifiers);
+    }
+
+    ///
+    /// Returns the action bound to the given input when the given modifiers are down, if any.
+    ///
+    pub fn action(&self, input: CameraInput, modifiers: Modifiers) -> Option<CameraAction> {
+        self.bindings
+            .iter()
+            .find(|b| b.matches(input, modifiers))
+            .map(|b| b.action)
+    }
+
+    ///
+    /// Returns all the bindings.
+    ///
+    pub fn bindings(&self) -> &[CameraBinding] {
+        &self.bindings
+    }
+
+    fn is_bound(&self, key: Key) -> bool {
+        self.bindings
+            .iter()
+            .any(|b| b.input == CameraInput::Key(key))
+    }
+
+    /// Returns the actions bound to the two inputs or `None` if neither of them are bound.
+    fn actions(
+        &self,
+        horizontal: CameraInput,
+        vertical: CameraInput,
+        modifiers: Modifiers,
+    ) -> Option<(CameraAction, CameraAction)> {
+        match (
+            self.action(horizontal, modifiers),
+            self.action(vertical, modifiers),
+        ) {
+            (None, None) => None,
+            (h, v) => Some((h.unwrap_or_default(), v.unwrap_or_default())),
+        }
+    }
+}
+
 ///
 /// A customizable controller for the camera.
 /// It is possible to specify a [CameraAction] for each of the input events.
+/// In addition, the [CameraControl::bindings] can bind combinations of inputs and modifiers, including keys, to actions.
+/// A binding which matches the input and modifiers takes precedence over the fixed actions specified for the input events.
 ///
-#[derive(Clone, Copy, Debug, Default)]
+/// **Note:** Since the [CameraControl::bindings] can contain any number of bindings and the control keeps track of the keys which are held down,
+/// `CameraControl` does not implement [Copy] anymore, use [Clone] instead.
+/// For the same reason, it cannot be created with a struct expression, instead create it using [CameraControl::new] and then set the actions.
+///
+#[derive(Clone, Debug, Default)]
 pub struct CameraControl {
     /// Specifies what happens when dragging horizontally with the left mouse button.
     pub left_drag_horizontal: CameraAction,
@@ -92,6 +258,19 @@ pub struct CameraControl {
     pub scroll_horizontal: CameraAction,
     /// Specifies what happens when scrolling vertically.
     pub scroll_vertical: CameraAction,
+    /// Specifies what happens when pinching with two fingers.
+    pub pinch: CameraAction,
+    /// Specifies what happens when panning horizontally with two fingers.
+    pub two_finger_pan_horizontal: CameraAction,
+    /// Specifies what happens when panning vertically with two fingers.
+    pub two_finger_pan_vertical: CameraAction,
+    /// Specifies what happens when rotating two fingers around each other.
+    pub rotate: CameraAction,
+    /// Bindings of combinations of inputs and modifiers to actions.
+    pub bindings: CameraBindings,
+    pressed_keys: Vec<Key>,
+    modifiers: Modifiers,
+    last_drag_position: Option<PhysicalPoint>,
 }
 
 impl CameraControl {
@@ -100,49 +279,204 @@ impl CameraControl {
         Self::default()
     }
 
+    ///
     /// Handles the events. Must be called each frame.
+    /// Actions bound to keys are not applied and the key events are not marked as handled, use [CameraControl::handle_events_with_elapsed_time] instead.
+    ///
     pub fn handle_events(&mut self, camera: &mut Camera, events: &mut [Event]) -> bool {
+        self.handle_events_with_elapsed_time(camera, events, 0.0)
+    }
+
+    ///
+    /// Handles the events and applies the actions bound to the keys which are held down,
+    /// scaled by the given elapsed time in milliseconds since the last frame (for example the `elapsed_time` of the frame input).
+    /// Must be called each frame.
+    ///
+    pub fn handle_events_with_elapsed_time(
+        &mut self,
+        camera: &mut Camera,
+        events: &mut [Event],
+        elapsed_time: f64,
+    ) -> bool {
         let mut change = false;
         for event in events.iter_mut() {
             match event {
+                Event::MousePress {
+                    position, handled, ..
+                } => {
+                    self.last_drag_position = if *handled { None } else { Some(*position) };
+                }
+                Event::MouseRelease { .. } => {
+                    self.last_drag_position = None;
+                }
                 Event::MouseMotion {
                     delta,
                     button,
+                    position,
+                    modifiers,
                     handled,
-                    ..
                 } => {
-                    if !*handled && button.is_some() {
-                        if let Some(b) = button {
-                            let (control_horizontal, control_vertical) = match b {
-                                MouseButton::Left => {
-                                    (self.left_drag_horizontal, self.left_drag_vertical)
-                                }
-                                MouseButton::Middle => {
-                                    (self.middle_drag_horizontal, self.middle_drag_vertical)
-                                }
-                                MouseButton::Right => {
-                                    (self.right_drag_horizontal, self.right_drag_vertical)
+                    if *handled {
+                        // The drag is continued from the next unhandled position
+                        self.last_drag_position = None;
+                    } else if let Some(b) = button {
+                        let last_position = self.last_drag_position.replace(*position);
+                        match self.drag_actions(*b, *modifiers) {
+                            (CameraAction::Arcball { target, speed }, _)
+                            | (_, CameraAction::Arcball { target, speed }) => {
+                                if let Some(last_position) = last_position {
+                                    arcball_rotation(
+                                        camera,
+                                        target,
+                                        speed,
+                                        last_position,
+                                        *position,
+                                    );
+                                    change = true;
                                 }
-                            };
-                            *handled = self.handle_action(camera, control_horizontal, delta.0);
-                            *handled |= self.handle_action(camera, control_vertical, delta.1);
-                            change |= *handled;
+                                *handled = true;
+                            }
+                            (control_horizontal, control_vertical) => {
+                                *handled = self.handle_action(camera, control_horizontal, delta.0);
+                                *handled |= self.handle_action(camera, control_vertical, delta.1);
+                                change |= *handled;
+                            }
                         }
                     }
                 }
-                Event::MouseWheel { delta, handled, .. } => {
+                Event::MouseWheel {
+                    delta,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
                     if !*handled {
-                        *handled = self.handle_action(camera, self.scroll_horizontal, delta.0);
-                        *handled |= self.handle_action(camera, self.scroll_vertical, delta.1);
+                        let (control_horizontal, control_vertical) = self
+                            .bindings
+                            .actions(
+                                CameraInput::ScrollHorizontal,
+                                CameraInput::ScrollVertical,
+                                *modifiers,
+                            )
+                            .unwrap_or((self.scroll_horizontal, self.scroll_vertical));
+                        *handled = self.handle_action(camera, control_horizontal, delta.0);
+                        *handled |= self.handle_action(camera, control_vertical, delta.1);
                         change |= *handled;
                     }
                 }
+                Event::Pinch {
+                    delta,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        let control = self
+                            .bindings
+                            .action(CameraInput::Pinch, *modifiers)
+                            .unwrap_or(self.pinch);
+                        *handled = self.handle_action(camera, control, *delta);
+                        change |= *handled;
+                    }
+                }
+                Event::TwoFingerPan {
+                    delta,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        let (control_horizontal, control_vertical) = self
+                            .bindings
+                            .actions(
+                                CameraInput::TwoFingerPanHorizontal,
+                                CameraInput::TwoFingerPanVertical,
+                                *modifiers,
+                            )
+                            .unwrap_or((
+                                self.two_finger_pan_horizontal,
+                                self.two_finger_pan_vertical,
+                            ));
+                        *handled = self.handle_action(camera, control_horizontal, delta.0);
+                        *handled |= self.handle_action(camera, control_vertical, delta.1);
+                        change |= *handled;
+                    }
+                }
+                Event::Rotate {
+                    angle,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        let control = self
+                            .bindings
+                            .action(CameraInput::Rotate, *modifiers)
+                            .unwrap_or(self.rotate);
+                        *handled = self.handle_action(camera, control, *angle);
+                        change |= *handled;
+                    }
+                }
+                Event::KeyPress {
+                    kind,
+                    modifiers,
+                    handled,
+                } => {
+                    self.modifiers = *modifiers;
+                    if !*handled && self.bindings.is_bound(*kind) {
+                        if !self.pressed_keys.contains(kind) {
+                            self.pressed_keys.push(*kind);
+                        }
+                        // The key is only used if the bound action can be applied
+                        *handled = elapsed_time > 0.0;
+                    }
+                }
+                Event::KeyRelease {
+                    kind, modifiers, ..
+                } => {
+                    self.modifiers = *modifiers;
+                    self.pressed_keys.retain(|k| k != kind);
+                }
+                Event::ModifiersChange { modifiers } => {
+                    self.modifiers = *modifiers;
+                }
                 _ => {}
             }
         }
+
+        // Continuous movement while the keys are held down
+        if elapsed_time > 0.0 {
+            let controls = self
+                .pressed_keys
+                .iter()
+                .filter_map(|key| self.bindings.action(CameraInput::Key(*key), self.modifiers))
+                .collect::<Vec<_>>();
+            for control in controls {
+                change |= self.handle_action(camera, control, elapsed_time as f32);
+            }
+        }
         change
     }
 
+    /// Returns the actions applied when dragging horizontally and vertically with the given button and modifiers.
+    fn drag_actions(
+        &self,
+        button: MouseButton,
+        modifiers: Modifiers,
+    ) -> (CameraAction, CameraAction) {
+        self.bindings
+            .actions(
+                CameraInput::DragHorizontal(button),
+                CameraInput::DragVertical(button),
+                modifiers,
+            )
+            .unwrap_or(match button {
+                MouseButton::Left => (self.left_drag_horizontal, self.left_drag_vertical),
+                MouseButton::Middle => (self.middle_drag_horizontal, self.middle_drag_vertical),
+                MouseButton::Right => (self.right_drag_horizontal, self.right_drag_vertical),
+            })
+    }
+
     fn handle_action(&mut self, camera: &mut Camera, control_type: CameraAction, x: f32) -> bool {
         match control_type {
             CameraAction::Pitch { speed } => {
@@ -182,8 +516,60 @@ impl CameraControl {
             } => {
                 camera.zoom_towards(&target, speed * x, min, max);
             }
-            CameraAction::None => {}
+            CameraAction::Arcball { .. } | CameraAction::None => {
+                return false;
+            }
         }
-        control_type != CameraAction::None
+        true
+    }
+}
+
+///
+/// Rotates the camera around the target such that the point on a virtual sphere centered in the viewport under the `from` pixel
+/// is moved to the point under the `to` pixel, scaled by the speed.
+///
+fn arcball_rotation(
+    camera: &mut Camera,
+    target: Vec3,
+    speed: f32,
+    from: PhysicalPoint,
+    to: PhysicalPoint,
+) {
+    let a = virtual_sphere_point(camera, from);
+    let b = virtual_sphere_point(camera, to);
+    let axis = a.cross(b);
+    if axis.magnitude2() < 0.0000000001 {
+        return;
     }
+    let angle = a.dot(b).clamp(-1.0, 1.0).acos() * speed;
+
+    // From view space to world space
+    let right = camera.right_direction().normalize();
+    let back = -camera.view_direction().normalize();
+    let up = back.cross(right);
+    let axis = (right * axis.x + up * axis.y + back * axis.z).normalize();
+
+    // The scene should follow the cursor, so the camera is rotated in the opposite direction
+    let rotation = Mat3::from_axis_angle(axis, radians(-angle));
+    let position = target + rotation * (*camera.position() - target);
+    let new_target = target + rotation * (*camera.target() - target);
+    let up = rotation * *camera.up();
+    camera.set_view(position, new_target, up);
+}
+
+///
+/// Returns the point in view space on the virtual sphere (with a hyperbolic sheet outside the sphere) under the given pixel.
+///
+fn virtual_sphere_point(camera: &Camera, pixel: PhysicalPoint) -> Vec3 {
+    let viewport = camera.viewport();
+    let radius = 0.5 * viewport.width.min(viewport.height).max(1) as f32;
+    let x = (pixel.x - viewport.x as f32 - 0.5 * viewport.width as f32) / radius;
+    let y = (pixel.y - viewport.y as f32 - 0.5 * viewport.height as f32) / radius;
+    let d2 = x * x + y * y;
+    let z = if d2 <= 0.5 {
+        (1.0 - d2).sqrt()
+    } else {
+        0.5 / d2.sqrt()
+    };
+    vec3(x, y, z).normalize()
 }
@@ -10,18 +10,16 @@ pub struct FirstPersonControl {
 impl FirstPersonControl {
     /// Creates a new first person control with the given speed of movements.
     pub fn new(speed: f32) -> Self {
-        Self {
-            control: CameraControl {
-                left_drag_horizontal: CameraAction::Yaw {
-                    speed: std::f32::consts::PI / 1800.0,
-                },
-                left_drag_vertical: CameraAction::Pitch {
-                    speed: std::f32::consts::PI / 1800.0,
-                },
-                scroll_vertical: CameraAction::Forward { speed },
-                ..Default::default()
-            },
-        }
+        let mut control = CameraControl::new();
+        control.left_drag_horizontal = CameraAction::Yaw {
+            speed: std::f32::consts::PI / 1800.0,
+        };
+        control.left_drag_vertical = CameraAction::Pitch {
+            speed: std::f32::consts::PI / 1800.0,
+        };
+        control.scroll_vertical = CameraAction::Forward { speed };
+        control.pinch = CameraAction::Forward { speed };
+        Self { control }
     }
 
     /// Handles the events. Must be called each frame.
@@ -64,6 +64,18 @@ impl FlyControl {
                     let scale = if delta.1 > 0.0 { 0.8 } else { 1.2 };
                     self.zoom_(camera, scale);
                 }
+                Event::Pinch {
+                    delta,
+                    position: _position,
+                    modifiers: _modifiers,
+                    handled,
+                } => {
+                    if !*handled {
+                        // 双指缩放
+                        self.zoom_(camera, (-0.005 * *delta).exp());
+                        *handled = true;
+                    }
+                }
                 Event::KeyPress {
                     kind,
                     modifiers: _modifiers,
@@ -10,18 +10,48 @@ pub struct OrbitControl {
 impl OrbitControl {
     /// Creates a new orbit control with the given target and minimum and maximum distance to the target.
     pub fn new(target: Vec3, min_distance: f32, max_distance: f32) -> Self {
-        Self {
-            control: CameraControl {
-                left_drag_horizontal: CameraAction::OrbitLeft { target, speed: 0.1 },
-                left_drag_vertical: CameraAction::OrbitUp { target, speed: 0.1 },
-                scroll_vertical: CameraAction::Zoom {
-                    min: min_distance,
-                    max: max_distance,
-                    speed: 0.1,
-                    target,
-                },
-                ..Default::default()
-            },
+        let mut control = CameraControl::new();
+        control.left_drag_horizontal = CameraAction::OrbitLeft { target, speed: 0.1 };
+        control.left_drag_vertical = CameraAction::OrbitUp { target, speed: 0.1 };
+        control.scroll_vertical = CameraAction::Zoom {
+            min: min_distance,
+            max: max_distance,
+            speed: 0.1,
+            target,
+        };
+        control.pinch = CameraAction::Zoom {
+            min: min_distance,
+            max: max_distance,
+            speed: 0.1,
+            target,
+        };
+        control.rotate = CameraAction::OrbitLeft { target, speed: 0.1 };
+        Self { control }
+    }
+
+    /// Returns the target which the camera orbits around and zooms towards.
+    pub fn target(&self) -> Vec3 {
+        match self.control.left_drag_horizontal {
+            CameraAction::OrbitLeft { target, .. } => target,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets the target which the camera orbits around and zooms towards, for example to the target returned by [CameraAnimator::fly_to_aabb].
+    pub fn set_target(&mut self, new_target: Vec3) {
+        for action in [
+            &mut self.control.left_drag_horizontal,
+            &mut self.control.left_drag_vertical,
+            &mut self.control.scroll_vertical,
+            &mut self.control.pinch,
+            &mut self.control.rotate,
+        ] {
+            match action {
+                CameraAction::OrbitLeft { target, .. }
+                | CameraAction::OrbitUp { target, .. }
+                | CameraAction::Zoom { target, .. } => *target = new_target,
+                _ => {}
+            }
         }
     }
 
@@ -31,6 +61,14 @@ impl OrbitControl {
             let x = target.distance(*camera.position());
             *speed = 0.002 * x + 0.0001;
         }
+        if let CameraAction::Zoom { speed, target, .. } = &mut self.control.pinch {
+            let x = target.distance(*camera.position());
+            *speed = 0.004 * x + 0.0001;
+        }
+        if let CameraAction::OrbitLeft { speed, target } = &mut self.control.rotate {
+            let x = target.distance(*camera.position());
+            *speed = 0.3 * x + 0.0001;
+        }
         if let CameraAction::OrbitLeft { speed, target } = &mut self.control.left_drag_horizontal {
             let x = target.distance(*camera.position());
             *speed = 0.001 * x + 0.0001;
@@ -0,0 +1,231 @@
+use crate::renderer::*;
+use three_d_asset::ProjectionType;
+
+///
+/// A control that rotates the camera around a pivot like a trackball, see [CameraAction::Arcball].
+/// Unlike [OrbitControl], the rotation does not keep a fixed up direction, so it does not gimbal at the poles and allows free roll,
+/// which is useful for inspecting arbitrary objects, for example in a CAD viewer.
+///
+/// 1. Left drag = rotate around the pivot
+/// 2. Shift + left drag horizontally = roll
+/// 3. Right or middle drag = pan
+/// 4. Scroll = zoom towards the cursor
+/// 5. Pinch = zoom, two finger pan = pan and two finger rotation = roll
+///
+/// Use [TrackballControl::handle_events_with_picking] to set the pivot to the point under the cursor when starting a rotation
+/// and to zoom towards the point under the cursor.
+///
+pub struct TrackballControl {
+    control: CameraControl,
+    pivot: Vec3,
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl TrackballControl {
+    /// Creates a new trackball control with the given pivot and minimum and maximum distance to the pivot.
+    pub fn new(pivot: Vec3, min_distance: f32, max_distance: f32) -> Self {
+        let mut control = CameraControl::new();
+        control.left_drag_horizontal = CameraAction::Arcball {
+            target: pivot,
+            speed: 1.0,
+        };
+        control.left_drag_vertical = CameraAction::Arcball {
+            target: pivot,
+            speed: 1.0,
+        };
+        control.right_drag_horizontal = CameraAction::Left { speed: 0.1 };
+        control.right_drag_vertical = CameraAction::Up { speed: 0.1 };
+        control.middle_drag_horizontal = CameraAction::Left { speed: 0.1 };
+        control.middle_drag_vertical = CameraAction::Up { speed: 0.1 };
+        control.pinch = CameraAction::Zoom {
+            target: pivot,
+            speed: 0.1,
+            min: min_distance,
+            max: max_distance,
+        };
+        control.two_finger_pan_horizontal = CameraAction::Left { speed: 0.1 };
+        control.two_finger_pan_vertical = CameraAction::Up { speed: 0.1 };
+        control.rotate = CameraAction::Roll { speed: -1.0 };
+        control.bindings.bind(
+            CameraInput::DragHorizontal(MouseButton::Left),
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+            CameraAction::Roll { speed: 0.01 },
+        );
+        Self {
+            control,
+            pivot,
+            min_distance,
+            max_distance,
+        }
+    }
+
+    /// Returns the pivot which the camera rotates around.
+    pub fn pivot(&self) -> Vec3 {
+        self.pivot
+    }
+
+    /// Sets the pivot which the camera rotates around, for example to the target returned by [CameraAnimator::fly_to_aabb].
+    pub fn set_pivot(&mut self, pivot: Vec3) {
+        self.pivot = pivot;
+        for action in [
+            &mut self.control.left_drag_horizontal,
+            &mut self.control.left_drag_vertical,
+            &mut self.control.pinch,
+        ] {
+            match action {
+                CameraAction::Arcball { target, .. } | CameraAction::Zoom { target, .. } => {
+                    *target = pivot
+                }
+                _ => {}
+            }
+        }
+    }
+
+    ///
+    /// Sets the pivot to the closest intersection between a ray from the camera in the given pixel coordinate and the given geometries (see [pick]).
+    /// Returns the new pivot or `None` if no geometry was hit, in which case the pivot is not changed.
+    ///
+    pub fn set_pivot_at_pixel(
+        &mut self,
+        context: &Context,
+        camera: &Camera,
+        pixel: impl Into<PhysicalPoint> + Copy,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+    ) -> Option<Vec3> {
+        let pivot = pick(context, camera, pixel, geometries)?;
+        self.set_pivot(pivot);
+        Some(pivot)
+    }
+
+    ///
+    /// Handles the events. Must be called each frame.
+    /// Zooming with the scroll wheel is towards the point under the cursor at the same depth as the pivot.
+    ///
+    pub fn handle_events(&mut self, camera: &mut Camera, events: &mut [Event]) -> bool {
+        let mut change = false;
+        for event in events.iter_mut() {
+            match event {
+                Event::MouseWheel {
+                    delta,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        let point = self.point_at_pivot_depth(camera, *position);
+                        self.zoom_towards(camera, *position, point, delta.1);
+                        *handled = true;
+                        change = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let distance = camera.position().distance(self.pivot);
+        let pan_speed = 0.001 * distance + 0.0001;
+        for action in [
+            &mut self.control.right_drag_horizontal,
+            &mut self.control.right_drag_vertical,
+            &mut self.control.middle_drag_horizontal,
+            &mut self.control.middle_drag_vertical,
+            &mut self.control.two_finger_pan_horizontal,
+            &mut self.control.two_finger_pan_vertical,
+        ] {
+            if let CameraAction::Left { speed } | CameraAction::Up { speed } = action {
+                *speed = pan_speed;
+            }
+        }
+        if let CameraAction::Zoom { speed, .. } = &mut self.control.pinch {
+            *speed = 0.004 * distance + 0.0001;
+        }
+        change |= self.control.handle_events(camera, events);
+        change
+    }
+
+    ///
+    /// Handles the events like [TrackballControl::handle_events], but in addition the pivot is set to the point on the given geometries
+    /// under the cursor when pressing the left mouse button and zooming with the scroll wheel is towards the point on the geometries under the cursor.
+    /// If no geometry is under the cursor, the pivot is not changed and the zoom is as in [TrackballControl::handle_events].
+    /// Must be called each frame.
+    ///
+    pub fn handle_events_with_picking<G: Geometry>(
+        &mut self,
+        context: &Context,
+        camera: &mut Camera,
+        events: &mut [Event],
+        geometries: impl IntoIterator<Item = G> + Clone,
+    ) -> bool {
+        let mut change = false;
+        for event in events.iter_mut() {
+            match event {
+                Event::MousePress {
+                    button: MouseButton::Left,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        self.set_pivot_at_pixel(context, camera, *position, geometries.clone());
+                    }
+                }
+                Event::MouseWheel {
+                    delta,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        let point = pick(context, camera, *position, geometries.clone())
+                            .unwrap_or_else(|| self.point_at_pivot_depth(camera, *position));
+                        self.zoom_towards(camera, *position, point, delta.1);
+                        *handled = true;
+                        change = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        change |= self.handle_events(camera, events);
+        change
+    }
+
+    /// Returns the point under the given pixel at the same depth as the pivot.
+    fn point_at_pivot_depth(&self, camera: &Camera, pixel: PhysicalPoint) -> Vec3 {
+        let position = camera.position_at_pixel(pixel);
+        let direction = camera.view_direction_at_pixel(pixel);
+        let view_direction = camera.view_direction();
+        let depth = (self.pivot - position).dot(view_direction);
+        let cos = direction.dot(view_direction).max(0.0001);
+        position + direction * (depth / cos)
+    }
+
+    /// Zooms towards the given point, which is kept under the given pixel.
+    fn zoom_towards(&mut self, camera: &mut Camera, pixel: PhysicalPoint, point: Vec3, delta: f32) {
+        let scale = (-0.005 * delta).exp();
+        let projection_type = camera.projection_type().clone();
+        match projection_type {
+            ProjectionType::Orthographic { height } => {
+                let new_height = (height * scale).clamp(self.min_distance, self.max_distance);
+                let scale = new_height / height;
+                let offset = camera.position_at_pixel(pixel) - *camera.position();
+                camera.translate(&(offset * (1.0 - scale)));
+                let (z_near, z_far) = (camera.z_near(), camera.z_far());
+                camera.set_orthographic_projection(new_height, z_near, z_far);
+            }
+            ProjectionType::Perspective { .. } => {
+                let distance = camera.position().distance(self.pivot).max(0.0001);
+                let scale = scale.clamp(
+                    self.min_distance / distance,
+                    (self.max_distance / distance).max(self.min_distance / distance),
+                );
+                let translation = (point - *camera.position()) * (1.0 - scale);
+                camera.translate(&translation);
+            }
+        }
+    }
+}
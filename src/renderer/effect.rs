@@ -64,6 +64,24 @@ mod water;
 #[doc(inline)]
 pub use water::*;
 
+mod outline;
+#[doc(inline)]
+pub use outline::*;
+
+mod ssao;
+#[doc(inline)]
+pub use ssao::*;
+
+mod bloom;
+#[doc(inline)]
+pub use bloom::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod hot_reload_effect;
+#[cfg(not(target_arch = "wasm32"))]
+#[doc(inline)]
+pub use hot_reload_effect::*;
+
 pub(crate) mod lighting_pass;
 
 use crate::renderer::*;
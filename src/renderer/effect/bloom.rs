@@ -0,0 +1,174 @@
+use crate::renderer::*;
+
+///
+/// Bloom, ie. bright parts of the image bleed into the surrounding area which simulates the glow of very bright light.
+/// Apply it by calling the `apply_bloom` method on a render target (for example [RenderTarget::apply_bloom]) with the scene rendered into a
+/// high dynamic range color texture, for example a [Texture2D] with `[f16; 4]` as data type, using a camera where tone and color mapping is disabled
+/// (see [Camera::disable_tone_and_color_mapping]).
+///
+/// The bright parts of the color texture are extracted, downsampled through the mip levels of a texture and upsampled again with a tent filter.
+/// Finally, the result is added to the color texture before the tone and color mapping of the camera given to `apply_bloom` is applied.
+///
+#[derive(Clone, Debug)]
+pub struct BloomEffect {
+    /// Colors with a brightness above this threshold contributes to the bloom.
+    pub threshold: f32,
+    /// Defines a smooth transition around the threshold, zero gives a hard cut-off.
+    pub knee: f32,
+    /// The strength of the bloom when added to the color texture.
+    pub intensity: f32,
+    /// The radius in texels of the tent filter used when upsampling, a larger value spreads the bloom further.
+    pub radius: f32,
+}
+
+impl Default for BloomEffect {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            knee: 0.5,
+            intensity: 0.1,
+            radius: 1.0,
+        }
+    }
+}
+
+///
+/// The maximum number of mip levels used for the bloom.
+///
+const MAX_BLOOM_LEVELS: u32 = 8;
+
+impl BloomEffect {
+    ///
+    /// Returns the number of mip levels used for bloom in a texture of the given size.
+    ///
+    pub(in crate::renderer) fn levels(width: u32, height: u32) -> u32 {
+        ((width.min(height).max(1) as f32).log2().floor() as u32).clamp(1, MAX_BLOOM_LEVELS)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(in crate::renderer) enum BloomPass {
+    Prefilter,
+    Downsample,
+    Upsample,
+    Composite,
+}
+
+///
+/// Used internally to apply one of the passes of the [BloomEffect].
+/// The bloom texture is sampled at the source level except for the prefilter pass which only uses the color texture.
+///
+pub(in crate::renderer) struct BloomPassEffect<'a> {
+    pub pass: BloomPass,
+    pub bloom: &'a BloomEffect,
+    pub bloom_texture: Option<&'a Texture2D>,
+    pub source_level: u32,
+}
+
+impl Effect for BloomPassEffect<'_> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        match self.pass {
+            BloomPass::Prefilter => format!(
+                "#define PREFILTER\n{}\n{}",
+                color_texture
+                    .expect("Must supply a color texture to apply a bloom effect")
+                    .fragment_shader_source(),
+                include_str!("shaders/bloom_effect.frag")
+            ),
+            BloomPass::Downsample => format!(
+                "#define DOWNSAMPLE\n{}",
+                include_str!("shaders/bloom_effect.frag")
+            ),
+            BloomPass::Upsample => format!(
+                "#define UPSAMPLE\n{}",
+                include_str!("shaders/bloom_effect.frag")
+            ),
+            BloomPass::Composite => format!(
+                "#define COMPOSITE\n{}\n{}\n{}\n{}",
+                color_texture
+                    .expect("Must supply a color texture to apply a bloom effect")
+                    .fragment_shader_source(),
+                ToneMapping::fragment_shader_source(),
+                ColorMapping::fragment_shader_source(),
+                include_str!("shaders/bloom_effect.frag")
+            ),
+        }
+    }
+
+    fn id(&self, color_texture: Option<ColorTexture>, _depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14
+            | 0b1u16 << 9
+            | (self.pass as u16) << 7
+            | color_texture.map(|t| t.id()).unwrap_or(0u16)
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes {
+            uv: true,
+            ..FragmentAttributes::NONE
+        }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(color_texture) = color_texture {
+            color_texture.use_uniforms(program);
+        }
+        match self.pass {
+            BloomPass::Prefilter => {
+                let color_texture =
+                    color_texture.expect("Must supply a color texture to apply a bloom effect");
+                program.use_uniform(
+                    "texelSize",
+                    vec2(
+                        1.0 / color_texture.width() as f32,
+                        1.0 / color_texture.height() as f32,
+                    ),
+                );
+                program.use_uniform("threshold", self.bloom.threshold);
+                program.use_uniform("knee", self.bloom.knee.max(0.0));
+            }
+            BloomPass::Downsample | BloomPass::Upsample => {
+                let bloom_texture = self.bloom_texture.unwrap();
+                program.use_texture("bloomMap", bloom_texture);
+                program.use_uniform("sourceLevel", self.source_level as f32);
+                let width = (bloom_texture.width() >> self.source_level).max(1);
+                let height = (bloom_texture.height() >> self.source_level).max(1);
+                program.use_uniform("texelSize", vec2(1.0 / width as f32, 1.0 / height as f32));
+                program.use_uniform_if_required("radius", self.bloom.radius);
+            }
+            BloomPass::Composite => {
+                camera.tone_mapping.use_uniforms(program);
+                camera.color_mapping.use_uniforms(program);
+                program.use_texture("bloomMap", self.bloom_texture.unwrap());
+                program.use_uniform("sourceLevel", self.source_level as f32);
+                program.use_uniform("intensity", self.bloom.intensity);
+            }
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: if self.pass == BloomPass::Upsample {
+                Blend::ADD
+            } else {
+                Blend::Disabled
+            },
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
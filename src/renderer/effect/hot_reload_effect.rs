@@ -0,0 +1,97 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::path::Path;
+
+///
+/// An effect meant for development which wraps another [Effect] but loads the fragment shader source from a file,
+/// so that the shader can be edited without restarting the application.
+/// Call [HotReloadEffect::update] from the render loop to reload the source when the file has changed.
+/// All other properties, for example the uniforms, the fragment attributes and the render states, are given by the wrapped effect.
+///
+/// **Note:** The content of the file is used as the entire fragment shader source, ie. the source code for the color and depth textures is not added,
+/// so the file must define the uniforms that the wrapped effect sends (see for example [ColorTexture::fragment_shader_source]).
+///
+pub struct HotReloadEffect<E: Effect> {
+    /// The wrapped effect.
+    pub effect: E,
+    source: HotReloadSource,
+}
+
+impl<E: Effect> HotReloadEffect<E> {
+    ///
+    /// Creates a new effect which uses the fragment shader source in the file at the given path together with the given effect.
+    /// Returns an error if the file cannot be read or if the source fails to compile.
+    ///
+    pub fn new(
+        context: &Context,
+        path: impl AsRef<Path>,
+        effect: E,
+    ) -> Result<Self, RendererError> {
+        let source = HotReloadSource::new(context, path, effect.fragment_attributes())?;
+        Ok(Self { effect, source })
+    }
+
+    ///
+    /// Checks if the file has been modified since it was last loaded and if so, reloads the fragment shader source.
+    /// If the new source compiles and links with the vertex shader of a [Mesh], the programs compiled with the previous source by this effect are removed from [Context::programs],
+    /// so they are compiled with the new source the next time they are used, and `true` is returned.
+    /// Otherwise the compilation error is returned and the last working source and programs are kept.
+    /// The error is only returned once for each modification of the file.
+    ///
+    pub fn update(&mut self) -> Result<bool, RendererError> {
+        self.source.update(self.effect.fragment_attributes())
+    }
+
+    ///
+    /// Returns the path to the file containing the fragment shader source.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.source.path
+    }
+
+    ///
+    /// Returns the last working fragment shader source.
+    ///
+    pub fn source(&self) -> &str {
+        &self.source.source
+    }
+}
+
+impl<E: Effect> Effect for HotReloadEffect<E> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        self.source.source.clone()
+    }
+
+    fn id(
+        &self,
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> u16 {
+        self.source.id
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        self.effect.fragment_attributes()
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.effect
+            .use_uniforms(program, camera, lights, color_texture, depth_texture)
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.effect.render_states()
+    }
+}
@@ -48,9 +48,7 @@ impl Effect for LightingPassEffect {
         color_texture.unwrap().use_uniforms(program);
         depth_texture.unwrap().use_uniforms(program);
         program.use_uniform_if_required("cameraPosition", camera.position());
-        for (i, light) in lights.iter().enumerate() {
-            light.use_uniforms(program, i as u32);
-        }
+        use_lights_uniforms(program, lights);
         program.use_uniform_if_required(
             "viewProjectionInverse",
             (camera.projection() * camera.view()).invert().unwrap(),
@@ -0,0 +1,146 @@
+use crate::renderer::*;
+
+///
+/// An effect that draws an outline around a set of objects, for example to highlight selected objects.
+/// The easiest way to use this effect is to call the `render_outline` method on a render target (for example [RenderTarget::render_outline])
+/// after the rest of the scene has been rendered.
+///
+/// When applied manually, the color texture must be an outline mask where the red channel is one where the objects are visible and zero elsewhere
+/// and the green channel contains the depth of the objects.
+/// The depth texture is the depth of the already rendered scene which, if specified, is used to draw the parts of the outline
+/// that are hidden behind other objects with the [OutlineEffect::occluded_color].
+///
+#[derive(Clone, Debug)]
+pub struct OutlineEffect {
+    /// The color of the outline.
+    pub color: Srgba,
+    /// The width of the outline in pixels.
+    pub width: f32,
+    /// The color of the parts of the outline that are hidden behind other objects.
+    /// If `None`, the hidden parts of the outline are not drawn.
+    pub occluded_color: Option<Srgba>,
+}
+
+impl Default for OutlineEffect {
+    fn default() -> Self {
+        Self {
+            color: Srgba::new_opaque(255, 165, 0),
+            width: 2.0,
+            occluded_color: None,
+        }
+    }
+}
+
+impl Effect for OutlineEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            color_texture
+                .expect("Must supply an outline mask as color texture to apply an outline effect")
+                .fragment_shader_source(),
+            depth_texture
+                .map(|t| format!("#define USE_DEPTH\n{}", t.fragment_shader_source()))
+                .unwrap_or_default(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/outline_effect.frag")
+        )
+    }
+
+    fn id(&self, color_texture: Option<ColorTexture>, depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14
+            | 0b1u16 << 7
+            | color_texture
+                .expect("Must supply an outline mask as color texture to apply an outline effect")
+                .id()
+            | depth_texture.map(|t| t.id()).unwrap_or(0u16)
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes {
+            uv: true,
+            ..FragmentAttributes::NONE
+        }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        camera.color_mapping.use_uniforms(program);
+        let color_texture = color_texture
+            .expect("Must supply an outline mask as color texture to apply an outline effect");
+        program.use_uniform(
+            "resolution",
+            vec2(color_texture.width() as f32, color_texture.height() as f32),
+        );
+        color_texture.use_uniforms(program);
+        if let Some(depth_texture) = depth_texture {
+            depth_texture.use_uniforms(program);
+        }
+        program.use_uniform("outlineColor", self.color.to_linear_srgb());
+        program.use_uniform(
+            "occludedColor",
+            self.occluded_color
+                .map(|c| c.to_linear_srgb())
+                .unwrap_or(vec4(0.0, 0.0, 0.0, 0.0)),
+        );
+        program.use_uniform("outlineWidth", self.width.max(0.0));
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Used internally to render the outline mask for the [OutlineEffect].
+/// Writes one to the red channel and the depth to the green channel.
+///
+#[derive(Clone)]
+pub(in crate::renderer) struct OutlineMaskMaterial {}
+
+impl Material for OutlineMaskMaterial {
+    fn id(&self) -> u16 {
+        0b1u16 << 15 | 0b1011u16
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        "
+        layout (location = 0) out vec4 outColor;
+        void main()
+        {
+            outColor = vec4(1.0, gl_FragCoord.z, 0.0, 0.0);
+        }
+        "
+        .to_owned()
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes::NONE
+    }
+
+    fn use_uniforms(&self, _program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {}
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
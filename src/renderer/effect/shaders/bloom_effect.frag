@@ -0,0 +1,65 @@
+
+uniform vec2 texelSize;
+
+#ifdef PREFILTER
+uniform float threshold;
+uniform float knee;
+#else
+uniform sampler2D bloomMap;
+uniform float sourceLevel;
+#endif
+
+#ifdef UPSAMPLE
+uniform float radius;
+#endif
+
+#ifdef COMPOSITE
+uniform float intensity;
+#endif
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+#ifndef PREFILTER
+vec3 sample_bloom(vec2 uv)
+{
+    return textureLod(bloomMap, uv, sourceLevel).rgb;
+}
+#endif
+
+void main()
+{
+#if defined(PREFILTER)
+    // Downsample to half resolution while extracting the bright parts with a soft threshold
+    vec3 color = 0.25 * (sample_color(uvs + texelSize * vec2(-0.5, -0.5)).rgb
+        + sample_color(uvs + texelSize * vec2(0.5, -0.5)).rgb
+        + sample_color(uvs + texelSize * vec2(-0.5, 0.5)).rgb
+        + sample_color(uvs + texelSize * vec2(0.5, 0.5)).rgb);
+    float brightness = max(color.r, max(color.g, color.b));
+    float soft = clamp(brightness - threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee + 0.00001);
+    float contribution = max(soft, brightness - threshold) / max(brightness, 0.00001);
+    outColor = vec4(color * contribution, 1.0);
+#elif defined(DOWNSAMPLE)
+    // Four bilinear samples which together covers a 4x4 texel box
+    vec3 color = 0.25 * (sample_bloom(uvs + texelSize * vec2(-1.0, -1.0))
+        + sample_bloom(uvs + texelSize * vec2(1.0, -1.0))
+        + sample_bloom(uvs + texelSize * vec2(-1.0, 1.0))
+        + sample_bloom(uvs + texelSize * vec2(1.0, 1.0)));
+    outColor = vec4(color, 1.0);
+#elif defined(UPSAMPLE)
+    // 3x3 tent filter
+    vec2 d = texelSize * radius;
+    vec3 color = 4.0 * sample_bloom(uvs);
+    color += 2.0 * (sample_bloom(uvs + vec2(-d.x, 0.0)) + sample_bloom(uvs + vec2(d.x, 0.0))
+        + sample_bloom(uvs + vec2(0.0, -d.y)) + sample_bloom(uvs + vec2(0.0, d.y)));
+    color += sample_bloom(uvs + vec2(-d.x, -d.y)) + sample_bloom(uvs + vec2(d.x, -d.y))
+        + sample_bloom(uvs + vec2(-d.x, d.y)) + sample_bloom(uvs + vec2(d.x, d.y));
+    outColor = vec4(color / 16.0, 1.0);
+#else
+    vec4 color = sample_color(uvs);
+    vec3 result = color.rgb + intensity * sample_bloom(uvs);
+    outColor = vec4(color_mapping(tone_mapping(result)), color.a);
+#endif
+}
@@ -0,0 +1,54 @@
+
+uniform vec2 resolution;
+uniform vec4 outlineColor;
+uniform vec4 occludedColor;
+uniform float outlineWidth;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // No outline on top of the objects themselves
+    if (sample_color(uvs).x > 0.5) {
+        discard;
+    }
+
+    vec2 texelSize = 1.0 / resolution;
+    int radius = int(ceil(outlineWidth));
+    float maxDistance = outlineWidth + 0.5;
+    float closest = maxDistance;
+    bool visible = false;
+    for (int y = -radius; y <= radius; y++) {
+        for (int x = -radius; x <= radius; x++) {
+            float dist = length(vec2(float(x), float(y)));
+            if (dist >= closest && visible) {
+                continue;
+            }
+            vec2 uv = uvs + vec2(float(x), float(y)) * texelSize;
+            vec4 mask = sample_color(uv);
+            if (mask.x > 0.5 && dist < maxDistance) {
+                closest = min(closest, dist);
+#ifdef USE_DEPTH
+                // The object is visible at this sample if nothing in the scene is in front of it
+                if (mask.y <= sample_depth(uv) + 0.00001) {
+                    visible = true;
+                }
+#else
+                visible = true;
+#endif
+            }
+        }
+    }
+    if (closest >= maxDistance) {
+        discard;
+    }
+
+    vec4 color = visible ? outlineColor : occludedColor;
+    color.a *= clamp(maxDistance - closest, 0.0, 1.0);
+    if (color.a <= 0.0) {
+        discard;
+    }
+    outColor = vec4(color_mapping(color.rgb), color.a);
+}
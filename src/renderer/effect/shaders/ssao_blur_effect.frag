@@ -0,0 +1,40 @@
+
+uniform mat4 projectionInverse;
+uniform vec2 resolution;
+uniform int blurRadius;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+float view_depth(vec2 uv)
+{
+    vec4 p = projectionInverse * vec4(uv * 2.0 - 1.0, sample_depth(uv) * 2.0 - 1.0, 1.0);
+    return p.z / p.w;
+}
+
+void main()
+{
+    if (sample_depth(uvs) > 0.99999) {
+        outColor = vec4(1.0);
+        return;
+    }
+    float centerDepth = view_depth(uvs);
+    float sigma = max(0.5 * float(blurRadius), 0.5);
+
+    float sum = 0.0;
+    float weightSum = 0.0;
+    for (int y = -blurRadius; y <= blurRadius; y++) {
+        for (int x = -blurRadius; x <= blurRadius; x++) {
+            vec2 uv = uvs + vec2(float(x), float(y)) / resolution;
+            // Gaussian weight in screen space and a weight that ignores samples with a very different depth
+            float spatialWeight = exp(-float(x * x + y * y) / (2.0 * sigma * sigma));
+            float depthWeight = max(0.0, 1.0 - abs(view_depth(uv) - centerDepth) / (0.05 * abs(centerDepth) + 0.0001));
+            float weight = spatialWeight * depthWeight;
+            sum += sample_color(uv).x * weight;
+            weightSum += weight;
+        }
+    }
+    float ao = weightSum > 0.0 ? sum / weightSum : sample_color(uvs).x;
+    outColor = vec4(ao, ao, ao, 1.0);
+}
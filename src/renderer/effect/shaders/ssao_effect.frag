@@ -0,0 +1,83 @@
+
+uniform mat4 projection;
+uniform mat4 projectionInverse;
+uniform vec2 resolution;
+uniform float radius;
+uniform float bias;
+uniform float intensity;
+uniform int sampleCount;
+
+#ifdef USE_NORMALS
+uniform mat4 view;
+#endif
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+vec3 view_position(vec2 uv)
+{
+    vec4 p = projectionInverse * vec4(uv * 2.0 - 1.0, sample_depth(uv) * 2.0 - 1.0, 1.0);
+    return p.xyz / p.w;
+}
+
+float random(vec2 co)
+{
+    return fract(sin(dot(co, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main()
+{
+    if (sample_depth(uvs) > 0.99999) {
+        outColor = vec4(1.0);
+        return;
+    }
+    vec3 position = view_position(uvs);
+
+#ifdef USE_NORMALS
+    // Same encoding as in the g-buffer
+    vec4 n = sample_color(uvs);
+    vec2 n2 = n.xy * 2.0 - 1.0;
+    float z = 1.0 - n2.x * n2.x - n2.y * n2.y;
+    if (z > 0.0001) {
+        z = sqrt(z);
+    }
+    vec3 normal = normalize(vec3(n2.x, n2.y, (int(floor(n.z * 255.0)) & 128) == 128 ? z: -z));
+    normal = normalize(mat3(view) * normal);
+#else
+    vec3 normal = normalize(cross(dFdx(position), dFdy(position)));
+#endif
+    if (dot(normal, position) > 0.0) {
+        normal = -normal;
+    }
+
+    // Rotate the sample kernel randomly per pixel, the noise is removed by the blur
+    float angle = 6.2831853 * random(uvs * resolution);
+    vec3 helper = abs(normal.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+    vec3 tangent = normalize(cross(helper, normal));
+    vec3 bitangent = cross(normal, tangent);
+
+    float occlusion = 0.0;
+    for (int i = 0; i < sampleCount; i++) {
+        // Cosine weighted spiral in the hemisphere around the normal
+        float t = (float(i) + 0.5) / float(sampleCount);
+        float phi = float(i) * 2.3999632 + angle;
+        float sinTheta = sqrt(t);
+        vec3 direction = (cos(phi) * tangent + sin(phi) * bitangent) * sinTheta + sqrt(1.0 - t) * normal;
+
+        // Place more samples close to the point
+        float scale = mix(0.1, 1.0, random(vec2(t, angle)));
+        vec3 samplePosition = position + direction * radius * scale * scale;
+
+        vec4 offset = projection * vec4(samplePosition, 1.0);
+        vec2 sampleUv = offset.xy / offset.w * 0.5 + 0.5;
+        if (sampleUv.x < 0.0 || sampleUv.x > 1.0 || sampleUv.y < 0.0 || sampleUv.y > 1.0) {
+            continue;
+        }
+        float sampleDepth = view_position(sampleUv).z;
+        float rangeCheck = smoothstep(0.0, 1.0, radius / abs(position.z - sampleDepth));
+        occlusion += (sampleDepth >= samplePosition.z + bias ? 1.0 : 0.0) * rangeCheck;
+    }
+    float ao = pow(clamp(1.0 - occlusion / float(sampleCount), 0.0, 1.0), intensity);
+    outColor = vec4(ao, ao, ao, 1.0);
+}
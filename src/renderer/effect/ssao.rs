@@ -0,0 +1,199 @@
+use crate::renderer::*;
+
+///
+/// Screen space ambient occlusion, ie. an effect that darkens creases, holes and surfaces that are close to each other
+/// based on the depth of the already rendered scene.
+/// The easiest way to use this effect is to call the `apply_ssao` method on a render target (for example [RenderTarget::apply_ssao])
+/// after the rest of the scene has been rendered, which computes the ambient occlusion, blurs it and multiplies it onto the render target.
+///
+/// When applied manually, the depth texture of the scene is required and the unblurred ambient occlusion is multiplied onto the render target.
+/// The color texture is optional and, if specified, must contain the normals of the scene encoded in the same way as in the second layer of the g-buffer
+/// produced by the [DeferredPhysicalMaterial]. If no normals are specified, they are reconstructed from the depth.
+///
+#[derive(Clone, Debug)]
+pub struct SsaoEffect {
+    /// The radius in world space units of the hemisphere around each point in which occluders are searched for.
+    pub radius: f32,
+    /// The number of samples per pixel, the maximum is 64.
+    pub samples: u32,
+    /// A bias in world space units which removes self-occlusion artifacts on flat surfaces.
+    pub bias: f32,
+    /// The power that the ambient occlusion is raised to, a higher value gives a darker result.
+    pub intensity: f32,
+    /// The radius in pixels of the depth aware (bilateral) blur applied by [RenderTarget::apply_ssao], zero means no blur.
+    pub blur_radius: u32,
+}
+
+impl Default for SsaoEffect {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            samples: 16,
+            bias: 0.025,
+            intensity: 1.0,
+            blur_radius: 4,
+        }
+    }
+}
+
+impl Effect for SsaoEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}",
+            color_texture
+                .map(|t| format!("#define USE_NORMALS\n{}", t.fragment_shader_source()))
+                .unwrap_or_default(),
+            depth_texture
+                .expect("Must supply a depth texture to apply a ssao effect")
+                .fragment_shader_source(),
+            include_str!("shaders/ssao_effect.frag")
+        )
+    }
+
+    fn id(&self, color_texture: Option<ColorTexture>, depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14
+            | 0b1u16 << 8
+            | color_texture.map(|t| t.id()).unwrap_or(0u16)
+            | depth_texture
+                .expect("Must supply a depth texture to apply a ssao effect")
+                .id()
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes {
+            uv: true,
+            ..FragmentAttributes::NONE
+        }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(color_texture) = color_texture {
+            color_texture.use_uniforms(program);
+            program.use_uniform("view", camera.view());
+        }
+        let depth_texture =
+            depth_texture.expect("Must supply a depth texture to apply a ssao effect");
+        depth_texture.use_uniforms(program);
+        program.use_uniform(
+            "resolution",
+            vec2(depth_texture.width() as f32, depth_texture.height() as f32),
+        );
+        program.use_uniform("projection", camera.projection());
+        program.use_uniform("projectionInverse", camera.projection().invert().unwrap());
+        program.use_uniform("radius", self.radius);
+        program.use_uniform("bias", self.bias);
+        program.use_uniform("intensity", self.intensity);
+        program.use_uniform("sampleCount", self.samples.clamp(1, 64) as i32);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: MULTIPLY,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Used internally to apply a depth aware blur to the ambient occlusion computed by the [SsaoEffect] and multiply it onto the render target.
+///
+#[derive(Clone, Debug)]
+pub(in crate::renderer) struct SsaoBlurEffect {
+    pub radius: u32,
+}
+
+impl Effect for SsaoBlurEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}",
+            color_texture
+                .expect("Must supply the ambient occlusion as color texture to apply a ssao blur")
+                .fragment_shader_source(),
+            depth_texture
+                .expect("Must supply a depth texture to apply a ssao blur")
+                .fragment_shader_source(),
+            include_str!("shaders/ssao_blur_effect.frag")
+        )
+    }
+
+    fn id(&self, color_texture: Option<ColorTexture>, depth_texture: Option<DepthTexture>) -> u16 {
+        0b1u16 << 14
+            | 0b1u16 << 8
+            | 0b1u16 << 7
+            | color_texture
+                .expect("Must supply the ambient occlusion as color texture to apply a ssao blur")
+                .id()
+            | depth_texture
+                .expect("Must supply a depth texture to apply a ssao blur")
+                .id()
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes {
+            uv: true,
+            ..FragmentAttributes::NONE
+        }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let color_texture = color_texture
+            .expect("Must supply the ambient occlusion as color texture to apply a ssao blur");
+        color_texture.use_uniforms(program);
+        depth_texture
+            .expect("Must supply a depth texture to apply a ssao blur")
+            .use_uniforms(program);
+        program.use_uniform(
+            "resolution",
+            vec2(color_texture.width() as f32, color_texture.height() as f32),
+        );
+        program.use_uniform("projectionInverse", camera.projection().invert().unwrap());
+        program.use_uniform("blurRadius", self.radius as i32);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: MULTIPLY,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+/// Multiplies the output color with the color in the render target and keeps the alpha in the render target.
+const MULTIPLY: Blend = Blend::Enabled {
+    source_rgb_multiplier: BlendMultiplierType::DstColor,
+    source_alpha_multiplier: BlendMultiplierType::Zero,
+    destination_rgb_multiplier: BlendMultiplierType::Zero,
+    destination_alpha_multiplier: BlendMultiplierType::One,
+    rgb_equation: BlendEquationType::Add,
+    alpha_equation: BlendEquationType::Add,
+};
@@ -103,9 +103,7 @@ impl Effect for WaterEffect {
         depth_texture
             .expect("Must supply a depth texture to apply a water effect")
             .use_uniforms(program);
-        for (i, light) in lights.iter().enumerate() {
-            light.use_uniforms(program, i as u32);
-        }
+        use_lights_uniforms(program, lights);
         program.use_uniform("viewProjection", camera.projection() * camera.view());
         program.use_uniform(
             "viewProjectionInverse",
@@ -50,6 +50,10 @@ macro_rules! impl_geometry_body {
         fn aabb(&self) -> AxisAlignedBoundingBox {
             self.$inner().aabb()
         }
+
+        fn outputs_instance_id(&self) -> bool {
+            self.$inner().outputs_instance_id()
+        }
     };
 }
 
@@ -61,6 +65,10 @@ mod instanced_mesh;
 #[doc(inline)]
 pub use instanced_mesh::*;
 
+mod instanced_lod_mesh;
+#[doc(inline)]
+pub use instanced_lod_mesh::*;
+
 mod sprites;
 #[doc(inline)]
 pub use sprites::*;
@@ -111,6 +119,11 @@ pub use three_d_asset::{
 /// - uv coordinates: `out vec2 uvs;` (must be flipped in v compared to standard uv coordinates, ie. do `uvs = vec2(uvs.x, 1.0 - uvs.y);` in the vertex shader or do the flip before constructing the uv coordinates vertex buffer)
 /// - color: `out vec4 col;`
 ///
+/// Furthermore, to support the instance index in [pick_with_info] and [select], the geometry should output the index of the instance, which is zero if the geometry is not instanced.
+/// - instance id: `flat out int instance_id;`
+///
+/// If the geometry does not output the instance id, which is specified by [Geometry::outputs_instance_id], the instance index is unknown, see [PickResult::instance_index] and [select].
+///
 pub trait Geometry {
     ///
     /// Draw this geometry.
@@ -162,6 +175,16 @@ pub trait Geometry {
     ///
     fn aabb(&self) -> AxisAlignedBoundingBox;
 
+    ///
+    /// Returns whether or not the vertex shader source returned from [Geometry::vertex_shader_source] outputs the instance id (`flat out int instance_id;`),
+    /// which is needed to find the instance index in [pick_with_info] and [select].
+    ///
+    /// The default implementation returns false.
+    ///
+    fn outputs_instance_id(&self) -> bool {
+        false
+    }
+
     ///
     /// For updating the animation of this geometry if it is animated, if not, this method does nothing.
     /// The time parameter should be some continious time, for example the time since start.
@@ -257,11 +280,37 @@ impl<T: Geometry> Geometry for std::sync::RwLock<T> {
         self.read().unwrap().aabb()
     }
 
+    fn outputs_instance_id(&self) -> bool {
+        self.read().unwrap().outputs_instance_id()
+    }
+
     fn animate(&mut self, time: f32) {
         self.write().unwrap().animate(time)
     }
 }
 
+///
+/// The maximum number of joints supported when skinning a [Mesh] or an [InstancedMesh], see [Mesh::set_joint_transformations].
+///
+pub const MAX_JOINTS: usize = 128;
+
+///
+/// The maximum number of morph targets supported by a [Mesh], see [Mesh::set_morph_targets].
+///
+pub const MAX_MORPH_TARGETS: usize = 4;
+
+///
+/// A morph target (also called blend shape) defined by a position offset and optionally a normal offset for each vertex of a [Mesh].
+/// The offsets are weighted by the weights given in [Mesh::set_morph_weights] and added to the vertex positions and normals.
+///
+#[derive(Clone, Debug, Default)]
+pub struct MorphTarget {
+    /// The offset of the position of each vertex.
+    pub positions: Vec<Vec3>,
+    /// The offset of the normal of each vertex.
+    pub normals: Option<Vec<Vec3>>,
+}
+
 struct BaseMesh {
     indices: Option<ElementBuffer>,
     positions: VertexBuffer,
@@ -269,6 +318,11 @@ struct BaseMesh {
     tangents: Option<VertexBuffer>,
     uvs: Option<VertexBuffer>,
     colors: Option<VertexBuffer>,
+    joints: Option<VertexBuffer>,
+    weights: Option<VertexBuffer>,
+    joint_transformations: Option<UniformBuffer>,
+    morph_targets: Vec<(VertexBuffer, Option<VertexBuffer>)>,
+    morph_weights: Vec<f32>,
 
     vao: Option<crate::context::VertexArray>,
 }
@@ -316,10 +370,134 @@ impl BaseMesh {
                     &data.iter().map(|c| c.to_linear_srgb()).collect::<Vec<_>>(),
                 )
             }),
+            joints: None,
+            weights: None,
+            joint_transformations: None,
+            morph_targets: Vec::new(),
+            morph_weights: Vec::new(),
             vao,
         }
     }
 
+    pub fn set_skinning_attributes(
+        &mut self,
+        context: &Context,
+        joints: &[Vector4<u16>],
+        weights: &[Vec4],
+    ) {
+        let vertex_count = self.positions.vertex_count();
+        if joints.len() as u32 != vertex_count {
+            panic!("Failed updating joints: The number of joints {} does not match the number of vertices {} in the mesh.", joints.len(), vertex_count)
+        }
+        if weights.len() as u32 != vertex_count {
+            panic!("Failed updating weights: The number of weights {} does not match the number of vertices {} in the mesh.", weights.len(), vertex_count)
+        }
+        self.joints = Some(VertexBuffer::new_with_data(context, joints));
+        self.weights = Some(VertexBuffer::new_with_data(context, weights));
+    }
+
+    pub fn set_joint_transformations(&mut self, context: &Context, joint_transformations: &[Mat4]) {
+        if joint_transformations.len() > MAX_JOINTS {
+            panic!(
+                "Failed updating joint transformations: The number of joints {} exceeds the maximum number of joints {}.",
+                joint_transformations.len(),
+                MAX_JOINTS
+            )
+        }
+        let mut data = vec![0.0; MAX_JOINTS * 16];
+        for (i, transformation) in joint_transformations.iter().enumerate() {
+            let m: &[f32; 16] = transformation.as_ref();
+            data[i * 16..(i + 1) * 16].copy_from_slice(m);
+        }
+        self.joint_transformations
+            .get_or_insert_with(|| UniformBuffer::new(context, &[MAX_JOINTS as u32 * 16]))
+            .update(0, &data);
+    }
+
+    pub fn is_skinned(&self) -> bool {
+        self.joints.is_some() && self.weights.is_some() && self.joint_transformations.is_some()
+    }
+
+    pub fn skinning_shader_source(&self) -> String {
+        if self.is_skinned() {
+            format!("#define USE_SKINNING\n#define MAX_JOINTS {}\n", MAX_JOINTS)
+        } else {
+            String::new()
+        }
+    }
+
+    pub fn set_morph_targets(&mut self, context: &Context, morph_targets: &[MorphTarget]) {
+        if morph_targets.len() > MAX_MORPH_TARGETS {
+            panic!(
+                "Failed updating morph targets: The number of morph targets {} exceeds the maximum number of morph targets {}.",
+                morph_targets.len(),
+                MAX_MORPH_TARGETS
+            )
+        }
+        let vertex_count = self.positions.vertex_count();
+        self.morph_targets = morph_targets
+            .iter()
+            .map(|morph_target| {
+                if morph_target.positions.len() as u32 != vertex_count {
+                    panic!("Failed updating morph targets: The number of positions {} does not match the number of vertices {} in the mesh.", morph_target.positions.len(), vertex_count)
+                }
+                (
+                    VertexBuffer::new_with_data(context, &morph_target.positions),
+                    morph_target.normals.as_ref().map(|normals| {
+                        if normals.len() as u32 != vertex_count {
+                            panic!("Failed updating morph targets: The number of normals {} does not match the number of vertices {} in the mesh.", normals.len(), vertex_count)
+                        }
+                        VertexBuffer::new_with_data(context, normals)
+                    }),
+                )
+            })
+            .collect();
+        self.morph_weights = vec![0.0; self.morph_targets.len()];
+    }
+
+    pub fn set_morph_weights(&mut self, weights: &[f32]) {
+        if weights.len() != self.morph_targets.len() {
+            panic!(
+                "Failed updating morph weights: The number of weights {} does not match the number of morph targets {}.",
+                weights.len(),
+                self.morph_targets.len()
+            )
+        }
+        self.morph_weights = weights.to_vec();
+    }
+
+    fn has_morph_normals(&self) -> bool {
+        !self.morph_targets.is_empty()
+            && self
+                .morph_targets
+                .iter()
+                .all(|(_, normals)| normals.is_some())
+    }
+
+    pub fn morph_targets_id(&self, attributes: FragmentAttributes) -> u16 {
+        let mut id = (self.morph_targets.len() as u16) << 9;
+        if attributes.normal && self.has_morph_normals() {
+            id |= 0b1u16 << 12;
+        }
+        id
+    }
+
+    pub fn morph_targets_shader_source(&self, attributes: FragmentAttributes) -> String {
+        if self.morph_targets.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "#define USE_MORPH_TARGETS\n#define MORPH_TARGET_COUNT {}\n{}",
+                self.morph_targets.len(),
+                if attributes.normal && self.has_morph_normals() {
+                    "#define USE_MORPH_NORMALS\n"
+                } else {
+                    ""
+                }
+            )
+        }
+    }
+
     pub fn draw(
         &self,
         program: &Program,
@@ -408,5 +586,48 @@ impl BaseMesh {
                 program.use_vertex_attribute("color", colors);
             }
         }
+
+        if program.requires_attribute("morph_position0") {
+            for (i, (positions, normals)) in self.morph_targets.iter().enumerate() {
+                program.use_vertex_attribute(&format!("morph_position{}", i), positions);
+                if let Some(normals) = normals {
+                    if attributes.normal
+                        && program.requires_attribute(&format!("morph_normal{}", i))
+                    {
+                        program.use_vertex_attribute(&format!("morph_normal{}", i), normals);
+                    }
+                }
+            }
+            program.use_uniform_array("morphWeights", &self.morph_weights);
+        }
+
+        if program.requires_attribute("joint_indices") {
+            if let (Some(joints), Some(weights)) = (&self.joints, &self.weights) {
+                program.use_vertex_attribute("joint_indices", joints);
+                program.use_vertex_attribute("joint_weights", weights);
+            }
+            if let Some(joint_transformations) = &self.joint_transformations {
+                program.use_uniform_block("JointTransformations", joint_transformations);
+            }
+        }
+    }
+}
+
+///
+/// Fills the instance buffer with the given name with the given data or creates it if it does not exist.
+///
+fn fill_instance_buffer<T: BufferDataType>(
+    context: &Context,
+    instance_buffers: &mut std::collections::HashMap<String, InstanceBuffer>,
+    name: &str,
+    data: &[T],
+) {
+    if let Some(instance_buffer) = instance_buffers.get_mut(name) {
+        instance_buffer.fill(data);
+    } else {
+        instance_buffers.insert(
+            name.to_string(),
+            InstanceBuffer::new_with_data(context, data),
+        );
     }
 }
@@ -0,0 +1,472 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::{fill_instance_buffer, BaseMesh};
+
+const INSTANCES_PER_CELL: usize = 64;
+const MAX_CELLS_PER_AXIS: usize = 32;
+const MAX_CACHED_VIEWS: usize = 16;
+
+struct VisibleInstances {
+    view_projection: Option<Mat4>,
+    transparent: bool,
+    levels: Vec<(u32, HashMap<String, InstanceBuffer>)>,
+}
+
+///
+/// Similar to [InstancedMesh], except that each instance is culled individually against the camera frustum
+/// and that each visible instance is rendered using one of several levels of detail depending on the distance to the camera.
+///
+/// The instances are organized in a uniform grid of cells, so that whole groups of instances outside the camera frustum are culled at once.
+/// The culling and level of detail selection is done on the CPU whenever the camera changes.
+/// The result is cached for each of the most recently used cameras, for example the camera of the main pass and the cameras of the shadow passes.
+///
+pub struct InstancedLodMesh {
+    context: Context,
+    levels: Vec<(BaseMesh, f32)>,
+    instances: Instances,
+    transformation: Mat4,
+    aabb_local: AxisAlignedBoundingBox,
+    aabb: AxisAlignedBoundingBox,
+    instance_aabbs: Vec<AxisAlignedBoundingBox>,
+    cells: Vec<(AxisAlignedBoundingBox, Vec<usize>)>,
+    visible_instances: RwLock<Vec<VisibleInstances>>,
+}
+
+impl InstancedLodMesh {
+    ///
+    /// Creates a new instanced 3D mesh with levels of detail from the given list of [CpuMesh]es and maximum distances.
+    /// The levels should be ordered from the most detailed to the least detailed and each instance is rendered with the first level where
+    /// the distance from the camera to the center of the instance is less than the maximum distance of the level.
+    /// Instances further away than the maximum distance of the last level are not rendered, use [f32::INFINITY] to always render them.
+    /// All data in the [CpuMesh]es is transfered to the GPU, so make sure to remove all unnecessary data from the [CpuMesh]es before calling this method.
+    ///
+    pub fn new(context: &Context, instances: &Instances, levels: &[(CpuMesh, f32)]) -> Self {
+        let mut aabb_local = AxisAlignedBoundingBox::EMPTY;
+        for (cpu_mesh, _) in levels.iter() {
+            aabb_local.expand_with_aabb(&cpu_mesh.compute_aabb());
+        }
+        let mut instanced_lod_mesh = Self {
+            context: context.clone(),
+            levels: levels
+                .iter()
+                .map(|(cpu_mesh, max_distance)| (BaseMesh::new(context, cpu_mesh), *max_distance))
+                .collect(),
+            instances: instances.clone(),
+            transformation: Mat4::identity(),
+            aabb_local,
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            instance_aabbs: Vec::new(),
+            cells: Vec::new(),
+            visible_instances: RwLock::new(Vec::new()),
+        };
+        instanced_lod_mesh.set_instances(instances);
+        instanced_lod_mesh
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all instances.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all instances.
+    /// This is applied after the transform for each instance.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.update_cells();
+    }
+
+    /// Returns the total number of instances.
+    pub fn instance_count(&self) -> u32 {
+        self.instances.count()
+    }
+
+    ///
+    /// Returns the number of instances rendered with each level of detail the last time this mesh was rendered.
+    ///
+    pub fn visible_instance_count(&self) -> Vec<u32> {
+        self.visible_instances
+            .read()
+            .unwrap()
+            .last()
+            .map(|visible_instances| {
+                visible_instances
+                    .levels
+                    .iter()
+                    .map(|(count, _)| *count)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Update the instances.
+    ///
+    pub fn set_instances(&mut self, instances: &Instances) {
+        #[cfg(debug_assertions)]
+        instances.validate().expect("invalid instances");
+        self.instances = instances.clone();
+        self.update_cells();
+    }
+
+    fn update_cells(&mut self) {
+        self.instance_aabbs = self
+            .instances
+            .transformations
+            .iter()
+            .map(|transformation| {
+                let mut aabb = self.aabb_local;
+                aabb.transform(&(self.transformation * transformation));
+                aabb
+            })
+            .collect();
+
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        self.instance_aabbs
+            .iter()
+            .for_each(|a| aabb.expand_with_aabb(a));
+        self.aabb = aabb;
+
+        let cells_per_axis = ((self.instance_aabbs.len() as f32 / INSTANCES_PER_CELL as f32)
+            .cbrt()
+            .ceil() as usize)
+            .clamp(1, MAX_CELLS_PER_AXIS);
+        let mut cells = vec![(AxisAlignedBoundingBox::EMPTY, Vec::new()); cells_per_axis.pow(3)];
+        if !aabb.is_empty() {
+            let min = aabb.min();
+            let size = aabb.size();
+            let cell_index = |v: f32, min: f32, size: f32| {
+                if size > 0.0 {
+                    (((v - min) / size * cells_per_axis as f32) as usize).min(cells_per_axis - 1)
+                } else {
+                    0
+                }
+            };
+            for (i, instance_aabb) in self.instance_aabbs.iter().enumerate() {
+                let center = instance_aabb.center();
+                let index = cell_index(center.x, min.x, size.x)
+                    + cells_per_axis
+                        * (cell_index(center.y, min.y, size.y)
+                            + cells_per_axis * cell_index(center.z, min.z, size.z));
+                cells[index].0.expand_with_aabb(instance_aabb);
+                cells[index].1.push(i);
+            }
+        }
+        cells.retain(|(_, indices)| !indices.is_empty());
+        self.cells = cells;
+        self.visible_instances
+            .write()
+            .unwrap()
+            .iter_mut()
+            .for_each(|visible_instances| visible_instances.view_projection = None);
+    }
+
+    ///
+    /// Culls the instances against the camera frustum, selects the level of detail for the visible instances and fills the instance buffers for each level.
+    /// The result is cached for each camera and moved to the end of the list of visible instances, where it is used when drawing.
+    /// If the camera is not cached, the instance buffers of the least recently used camera are reused.
+    ///
+    fn update_visible_instances(&self, camera: &Camera, transparent: bool) {
+        let view_projection = camera.projection() * camera.view();
+        let mut cache = self.visible_instances.write().unwrap();
+        if let Some(index) = cache.iter().position(|visible_instances| {
+            visible_instances.view_projection == Some(view_projection)
+                && visible_instances.transparent == transparent
+        }) {
+            let visible_instances = cache.remove(index);
+            cache.push(visible_instances);
+            return;
+        }
+        let mut visible_instances = if cache.len() >= MAX_CACHED_VIEWS {
+            cache.remove(0)
+        } else {
+            VisibleInstances {
+                view_projection: None,
+                transparent,
+                levels: Vec::new(),
+            }
+        };
+        visible_instances.view_projection = Some(view_projection);
+        visible_instances.transparent = transparent;
+
+        let position = *camera.position();
+        let mut level_indices = vec![Vec::new(); self.levels.len()];
+        for (cell_aabb, indices) in self.cells.iter() {
+            if !camera.in_frustum(cell_aabb) {
+                continue;
+            }
+            for i in indices.iter() {
+                let instance_aabb = &self.instance_aabbs[*i];
+                if !camera.in_frustum(instance_aabb) {
+                    continue;
+                }
+                let distance = instance_aabb.center().distance(position);
+                if let Some(level) = self
+                    .levels
+                    .iter()
+                    .position(|(_, max_distance)| distance < *max_distance)
+                {
+                    level_indices[level].push((*i, distance));
+                }
+            }
+        }
+
+        visible_instances
+            .levels
+            .resize_with(self.levels.len(), || (0, HashMap::new()));
+        for ((instance_count, instance_buffers), mut indices) in visible_instances
+            .levels
+            .iter_mut()
+            .zip(level_indices.into_iter())
+        {
+            if transparent {
+                // Render the instances from back to front.
+                indices.sort_by(|(_, a), (_, b)| {
+                    b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal)
+                });
+            }
+            let indices = indices.into_iter().map(|(i, _)| i).collect::<Vec<_>>();
+            *instance_count = indices.len() as u32;
+            self.fill_instance_buffers(instance_buffers, &indices);
+        }
+        cache.push(visible_instances);
+    }
+
+    fn fill_instance_buffers(
+        &self,
+        instance_buffers: &mut HashMap<String, InstanceBuffer>,
+        indices: &[usize],
+    ) {
+        if indices.is_empty() {
+            return;
+        }
+        fill_instance_buffer(
+            &self.context,
+            instance_buffers,
+            "instance_index",
+            &indices.iter().map(|i| *i as u32).collect::<Vec<_>>(),
+        );
+
+        let mut row1 = Vec::new();
+        let mut row2 = Vec::new();
+        let mut row3 = Vec::new();
+        for transformation in indices.iter().map(|i| self.instances.transformations[*i]) {
+            row1.push(transformation.row(0));
+            row2.push(transformation.row(1));
+            row3.push(transformation.row(2));
+        }
+        fill_instance_buffer(&self.context, instance_buffers, "row1", &row1);
+        fill_instance_buffer(&self.context, instance_buffers, "row2", &row2);
+        fill_instance_buffer(&self.context, instance_buffers, "row3", &row3);
+
+        if let Some(texture_transforms) = &self.instances.texture_transformations {
+            let mut instance_tex_transform1 = Vec::new();
+            let mut instance_tex_transform2 = Vec::new();
+            for texture_transform in indices.iter().map(|i| texture_transforms[*i]) {
+                instance_tex_transform1.push(vec3(
+                    texture_transform.x.x,
+                    texture_transform.y.x,
+                    texture_transform.z.x,
+                ));
+                instance_tex_transform2.push(vec3(
+                    texture_transform.x.y,
+                    texture_transform.y.y,
+                    texture_transform.z.y,
+                ));
+            }
+            fill_instance_buffer(
+                &self.context,
+                instance_buffers,
+                "tex_transform_row1",
+                &instance_tex_transform1,
+            );
+            fill_instance_buffer(
+                &self.context,
+                instance_buffers,
+                "tex_transform_row2",
+                &instance_tex_transform2,
+            );
+        }
+        if let Some(instance_colors) = &self.instances.colors {
+            fill_instance_buffer(
+                &self.context,
+                instance_buffers,
+                "instance_color",
+                &indices
+                    .iter()
+                    .map(|i| instance_colors[*i].to_linear_srgb())
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    fn has_vertex_colors(&self) -> bool {
+        self.levels
+            .iter()
+            .all(|(base_mesh, _)| base_mesh.colors.is_some())
+    }
+}
+
+impl<'a> IntoIterator for &'a InstancedLodMesh {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for InstancedLodMesh {
+    fn draw(
+        &self,
+        camera: &Camera,
+        program: &Program,
+        render_states: RenderStates,
+        attributes: FragmentAttributes,
+    ) {
+        self.update_visible_instances(camera, render_states.blend != Blend::Disabled);
+
+        camera.use_uniform_block(program);
+        program.use_uniform("modelMatrix", self.transformation);
+
+        let cache = self.visible_instances.read().unwrap();
+        let visible_instances = cache.last().unwrap();
+        let mut levels = self
+            .levels
+            .iter()
+            .zip(visible_instances.levels.iter())
+            .collect::<Vec<_>>();
+        if render_states.blend != Blend::Disabled {
+            // The levels are ordered from near to far, so draw them in reverse to render the instances back to front
+            levels.reverse();
+        }
+        for ((base_mesh, _), (instance_count, instance_buffers)) in levels {
+            if *instance_count == 0 {
+                continue;
+            }
+            program.use_vertex_array(base_mesh.vao);
+            for attribute_name in [
+                "row1",
+                "row2",
+                "row3",
+                "tex_transform_row1",
+                "tex_transform_row2",
+                "instance_color",
+                "instance_index",
+            ] {
+                if program.requires_attribute(attribute_name) {
+                    program.use_instance_attribute(
+                        attribute_name,
+                        instance_buffers
+                        .get(attribute_name).unwrap_or_else(|| panic!("the render call requires the {} instance buffer which is missing on the given geometry", attribute_name))
+                    );
+                }
+            }
+            base_mesh.draw_instanced(program, render_states, camera, attributes, *instance_count);
+        }
+    }
+
+    fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
+        format!(
+            "#define USE_INSTANCE_INDICES\n#define USE_INSTANCE_TRANSFORMS\n{}{}{}{}{}{}{}{}{}",
+            if required_attributes.normal {
+                "#define USE_NORMALS\n"
+            } else {
+                ""
+            },
+            if required_attributes.tangents {
+                "#define USE_TANGENTS\n"
+            } else {
+                ""
+            },
+            if required_attributes.uv {
+                "#define USE_UVS\n"
+            } else {
+                ""
+            },
+            if required_attributes.color && self.has_vertex_colors() {
+                "#define USE_VERTEX_COLORS\n"
+            } else {
+                ""
+            },
+            if required_attributes.color && self.instances.colors.is_some() {
+                "#define USE_INSTANCE_COLORS\n"
+            } else {
+                ""
+            },
+            if required_attributes.uv && self.instances.texture_transformations.is_some() {
+                "#define USE_INSTANCE_TEXTURE_TRANSFORMATION\n"
+            } else {
+                ""
+            },
+            include_str!("../../core/shared.frag"),
+            Camera::uniform_block_source(),
+            include_str!("shaders/mesh.vert"),
+        )
+    }
+
+    fn id(&self, required_attributes: FragmentAttributes) -> u16 {
+        let mut id = 0b1u16 << 15 | 0b1u16 << 6;
+        if required_attributes.normal {
+            id |= 0b1u16;
+        }
+        if required_attributes.tangents {
+            id |= 0b1u16 << 1;
+        }
+        if required_attributes.uv {
+            id |= 0b1u16 << 2;
+        }
+        if required_attributes.color && self.has_vertex_colors() {
+            id |= 0b1u16 << 3;
+        }
+        if required_attributes.color && self.instances.colors.is_some() {
+            id |= 0b1u16 << 4;
+        }
+        if required_attributes.uv && self.instances.texture_transformations.is_some() {
+            id |= 0b1u16 << 5;
+        }
+        id
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn outputs_instance_id(&self) -> bool {
+        true
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        render_with_material(&self.context, camera, self, material, lights)
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        render_with_effect(
+            &self.context,
+            camera,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        )
+    }
+}
@@ -3,7 +3,81 @@ use crate::renderer::*;
 use std::collections::HashMap;
 use std::sync::RwLock;
 
-use super::BaseMesh;
+use super::{fill_instance_buffer, BaseMesh};
+
+// The maximum number of animation clips that are blended when evaluating the animation of each instance.
+const MAX_INSTANCE_ANIMATION_CLIPS: usize = 4;
+// The number of times an animation clip is sampled between zero and the duration of the clip.
+const INSTANCE_ANIMATION_SAMPLE_COUNT: usize = 128;
+
+///
+/// An animation clip sampled at regular intervals, so that it can be evaluated in the vertex shader at the animation time of each instance (see [Instances::animation_time]).
+///
+pub(in crate::renderer) struct InstanceAnimationClip {
+    name: Option<String>,
+    duration: f32,
+    looping: bool,
+    transformations: Vec<Mat4>,
+    first_row: u32,
+}
+
+impl InstanceAnimationClip {
+    ///
+    /// Samples the given animation function between zero and the given duration.
+    /// If `looping` is true, the clip is repeated after the duration, otherwise the last sample is used after the duration.
+    ///
+    pub fn new(
+        name: Option<String>,
+        duration: f32,
+        looping: bool,
+        animation: impl Fn(f32) -> Mat4,
+    ) -> Self {
+        let sample_count = if duration > 0.0 {
+            INSTANCE_ANIMATION_SAMPLE_COUNT
+        } else {
+            1
+        };
+        let transformations = (0..sample_count)
+            .map(|i| animation(duration * i as f32 / (sample_count - 1).max(1) as f32))
+            .collect();
+        Self {
+            name,
+            duration: duration.max(0.0),
+            looping,
+            transformations,
+            first_row: 0,
+        }
+    }
+}
+
+impl From<&KeyFrameAnimation> for InstanceAnimationClip {
+    fn from(animation: &KeyFrameAnimation) -> Self {
+        let duration = animation
+            .key_frames
+            .iter()
+            .map(|(_, key_frames)| {
+                key_frames
+                    .loop_time
+                    .or_else(|| key_frames.times.last().copied())
+                    .unwrap_or(0.0)
+            })
+            .fold(0.0, f32::max);
+        let looping = !animation.key_frames.is_empty()
+            && animation
+                .key_frames
+                .iter()
+                .all(|(_, key_frames)| key_frames.loop_time.is_some());
+        Self::new(animation.name.clone(), duration, looping, |time| {
+            animation.transformation(time)
+        })
+    }
+}
+
+struct InstanceAnimation {
+    texture: Texture2D,
+    clips: Vec<InstanceAnimationClip>,
+    playing: Vec<(Vec4, Vec2)>,
+}
 
 ///
 /// Similar to [Mesh], except it is possible to render many instances of the same mesh efficiently.
@@ -11,13 +85,15 @@ use super::BaseMesh;
 pub struct InstancedMesh {
     context: Context,
     base_mesh: BaseMesh,
-    instance_buffers: RwLock<(HashMap<String, InstanceBuffer>, Vec3)>,
+    instance_buffers: RwLock<(HashMap<String, InstanceBuffer>, Option<Vec3>)>,
     aabb: AxisAlignedBoundingBox,
     aabb_local: AxisAlignedBoundingBox,
     transformation: Mat4,
     current_transformation: Mat4,
     animation: Option<Box<dyn Fn(f32) -> Mat4 + Send + Sync>>,
     instances: Instances,
+    instance_animation: Option<InstanceAnimation>,
+    instance_joint_transformations: Option<Texture2D>,
 }
 
 impl InstancedMesh {
@@ -31,13 +107,15 @@ impl InstancedMesh {
         let mut instanced_mesh = Self {
             context: context.clone(),
             base_mesh: BaseMesh::new(context, cpu_mesh),
-            instance_buffers: RwLock::new((Default::default(), vec3(0.0, 0.0, 0.0))),
+            instance_buffers: RwLock::new((Default::default(), None)),
             aabb,
             aabb_local: aabb,
             transformation: Mat4::identity(),
             current_transformation: Mat4::identity(),
             animation: None,
             instances: instances.clone(),
+            instance_animation: None,
+            instance_joint_transformations: None,
         };
         instanced_mesh.set_instances(instances);
         instanced_mesh
@@ -63,9 +141,212 @@ impl InstancedMesh {
     /// Specifies a function which takes a time parameter as input and returns a transformation that should be applied to this mesh at the given time.
     /// To actually animate this instanced mesh, call [Geometry::animate] at each frame which in turn evaluates the animation function defined by this method.
     /// This transformation is applied first, then the local to world transformation defined by [Self::set_transformation].
+    /// The animation function is evaluated once for all instances, use [Self::set_instance_animation] to play the animation at the individual animation time of each instance.
     ///
     pub fn set_animation(&mut self, animation: impl Fn(f32) -> Mat4 + Send + Sync + 'static) {
         self.animation = Some(Box::new(animation));
+        if self.instance_animation.take().is_some() {
+            self.update_aabb();
+            let camera_position = self.instance_buffers.read().unwrap().1;
+            self.update_instance_buffers(camera_position);
+        }
+    }
+
+    ///
+    /// Specifies an animation which is played at the individual animation time of each instance (see [Instances::animation_time]) when calling [Geometry::animate].
+    /// The animation function is sampled between zero and the given duration when calling this method and the samples are interpolated on the GPU,
+    /// so the cost of animating does not depend on the number of instances.
+    /// If `looping` is true, the animation is repeated after the duration, otherwise it stops at the end.
+    /// This replaces the animation defined by [Self::set_animation].
+    ///
+    pub fn set_instance_animation(
+        &mut self,
+        animation: impl Fn(f32) -> Mat4,
+        duration: f32,
+        looping: bool,
+    ) {
+        self.set_instance_animation_clips(vec![InstanceAnimationClip::new(
+            None, duration, looping, animation,
+        )]);
+    }
+
+    ///
+    /// Specifies the animation clips which are played at the individual animation time of each instance using [Self::play_instance_animation_clips].
+    ///
+    pub(in crate::renderer) fn set_instance_animation_clips(
+        &mut self,
+        mut clips: Vec<InstanceAnimationClip>,
+    ) {
+        // One row for each sample containing the translation, the rotation and the scale
+        let mut data = Vec::new();
+        for clip in clips.iter_mut() {
+            clip.first_row = (data.len() / 3) as u32;
+            for transformation in clip.transformations.iter() {
+                let (translation, rotation, scale) = decompose_transformation(transformation);
+                data.push([translation.x, translation.y, translation.z, 0.0]);
+                data.push([rotation.v.x, rotation.v.y, rotation.v.z, rotation.s]);
+                data.push([scale.x, scale.y, scale.z, 0.0]);
+            }
+        }
+        let height = (data.len() / 3).max(1) as u32;
+        data.resize(3 * height as usize, [0.0; 4]);
+        let mut texture = Texture2D::new_empty::<[f32; 4]>(
+            &self.context,
+            3,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        texture.fill(&data);
+        self.animation = None;
+        self.current_transformation = self.transformation;
+        self.instance_animation = Some(InstanceAnimation {
+            texture,
+            clips,
+            playing: Vec::new(),
+        });
+        self.update_aabb();
+        // Keep the order of the instances if they are sorted by the distance to the camera
+        let camera_position = self.instance_buffers.read().unwrap().1;
+        self.update_instance_buffers(camera_position);
+    }
+
+    ///
+    /// Plays the animation clips defined by [Self::set_instance_animation_clips] with the same name as the given samples at the time and with the weight of the samples.
+    /// The time of each sample is converted to the animation time of each instance (see [Instances::animation_time]) in the vertex shader.
+    ///
+    pub(in crate::renderer) fn play_instance_animation_clips(
+        &mut self,
+        samples: &[AnimationSample],
+    ) {
+        if let Some(instance_animation) = &mut self.instance_animation {
+            let playing = samples
+                .iter()
+                .filter(|sample| sample.weight > 0.0)
+                .filter_map(|sample| {
+                    instance_animation
+                        .clips
+                        .iter()
+                        .find(|clip| clip.name == sample.name)
+                        .map(|clip| {
+                            (
+                                vec4(
+                                    clip.first_row as f32,
+                                    clip.transformations.len() as f32,
+                                    clip.duration,
+                                    if clip.looping { 1.0 } else { 0.0 },
+                                ),
+                                vec2(sample.time, sample.weight),
+                            )
+                        })
+                })
+                .take(MAX_INSTANCE_ANIMATION_CLIPS)
+                .collect();
+            instance_animation.playing = playing;
+        }
+    }
+
+    ///
+    /// Sets the joint indices and weights for each vertex which are used to deform this instanced mesh by the joint transformations defined by [Self::set_joint_transformations].
+    /// Each vertex is influenced by up to four joints where the weights should sum to one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of joints or weights does not match the number of vertices in the mesh.
+    pub fn set_skinning_attributes(&mut self, joints: &[Vector4<u16>], weights: &[Vec4]) {
+        self.base_mesh
+            .set_skinning_attributes(&self.context, joints, weights);
+    }
+
+    ///
+    /// Sets the transformations of the joints used for skinning, ie. for each joint the global joint transformation multiplied by the inverse bind matrix (see [Skin::joint_transformations]).
+    /// The skinning is applied before any other transformation and only if the joint indices and weights are specified using [Self::set_skinning_attributes].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of joint transformations exceeds [MAX_JOINTS].
+    pub fn set_joint_transformations(&mut self, joint_transformations: &[Mat4]) {
+        self.instance_joint_transformations = None;
+        self.base_mesh
+            .set_joint_transformations(&self.context, joint_transformations);
+    }
+
+    ///
+    /// Sets the transformations of the joints used for skinning for each instance individually, ie. one list of joint transformations for each instance (see [Self::set_joint_transformations]).
+    /// This is for example used when the instances play the same skin animation at different times (see [Instances::animation_offsets]).
+    /// Evaluating a joint hierarchy requires the whole skeleton, so unlike [Self::set_instance_animation], the joint transformations are computed on the CPU and uploaded for each instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of lists does not match the number of instances or if the number of joint transformations exceeds [MAX_JOINTS].
+    pub fn set_instance_joint_transformations(&mut self, joint_transformations: &[Vec<Mat4>]) {
+        if joint_transformations.len() as u32 != self.instance_count() {
+            panic!("Failed updating joint transformations: The number of joint transformation lists {} does not match the number of instances {}.", joint_transformations.len(), self.instance_count())
+        }
+        let joint_count = joint_transformations
+            .iter()
+            .map(|t| t.len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        if joint_count > MAX_JOINTS {
+            panic!(
+                "Failed updating joint transformations: The number of joints {} exceeds the maximum number of joints {}.",
+                joint_count,
+                MAX_JOINTS
+            )
+        }
+        let width = 4 * joint_count as u32;
+        let height = self.instance_count().max(1);
+        let mut data = vec![[0.0f32; 4]; (width * height) as usize];
+        for (instance, transformations) in joint_transformations.iter().enumerate() {
+            for (joint, transformation) in transformations.iter().enumerate() {
+                let offset = instance * width as usize + 4 * joint;
+                data[offset] = transformation.x.into();
+                data[offset + 1] = transformation.y.into();
+                data[offset + 2] = transformation.z.into();
+                data[offset + 3] = transformation.w.into();
+            }
+        }
+        if self
+            .instance_joint_transformations
+            .as_ref()
+            .map(|t| t.width() != width || t.height() != height)
+            .unwrap_or(true)
+        {
+            self.instance_joint_transformations = Some(Texture2D::new_empty::<[f32; 4]>(
+                &self.context,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ));
+        }
+        self.instance_joint_transformations
+            .as_mut()
+            .unwrap()
+            .fill(&data);
+        self.base_mesh.joint_transformations = None;
+    }
+
+    fn is_skinned(&self) -> bool {
+        self.base_mesh.is_skinned()
+            || (self.base_mesh.joints.is_some()
+                && self.base_mesh.weights.is_some()
+                && self.instance_joint_transformations.is_some())
+    }
+
+    ///
+    /// Returns the instances.
+    ///
+    pub fn instances(&self) -> &Instances {
+        &self.instances
     }
 
     /// Returns the number of instances that is rendered.
@@ -80,6 +361,7 @@ impl InstancedMesh {
         #[cfg(debug_assertions)]
         instances.validate().expect("invalid instances");
         self.instances = instances.clone();
+        self.instance_joint_transformations = None;
         self.update_aabb();
 
         self.update_instance_buffers(None);
@@ -92,20 +374,34 @@ impl InstancedMesh {
             aabb2.transform(&(transformation * self.transformation));
             aabb.expand_with_aabb(&aabb2);
         }
+        if let Some(instance_animation) = &self.instance_animation {
+            // Include all the sampled animation transformations since the animation time of each instance is only known on the GPU
+            let mut animated_aabb = AxisAlignedBoundingBox::EMPTY;
+            for transformation in instance_animation
+                .clips
+                .iter()
+                .flat_map(|clip| clip.transformations.iter())
+            {
+                let mut aabb2 = aabb;
+                aabb2.transform(transformation);
+                animated_aabb.expand_with_aabb(&aabb2);
+            }
+            aabb = animated_aabb;
+        }
         self.aabb = aabb;
     }
 
     ///
-    /// This function creates the instance buffers, ordering them by distance to the camera
+    /// This function creates or updates the instance buffers, ordering them by distance to the given camera position if any.
+    /// Existing buffers are reused and filled with the new data.
     ///
-    fn update_instance_buffers(&self, camera: Option<&Camera>) {
+    fn update_instance_buffers(&self, camera_position: Option<Vec3>) {
         let mut s = self.instance_buffers.write().unwrap();
-        let indices = if let Some(position) = camera.map(|c| *c.position()) {
-            s.1 = position;
+        s.1 = camera_position;
+        let transformations = &self.instances.transformations;
+        let indices = if let Some(position) = camera_position {
             // Need to order by using the position.
-            let distances = self
-                .instances
-                .transformations
+            let distances = transformations
                 .iter()
                 .map(|m| (self.transformation * m).w.truncate().distance2(position))
                 .collect::<Vec<_>>();
@@ -118,51 +414,53 @@ impl InstancedMesh {
             indices
         } else {
             // No need to order, just return the indices as is.
-            (0..self.instances.transformations.len()).collect::<Vec<usize>>()
+            (0..transformations.len()).collect::<Vec<usize>>()
         };
 
         // Next, we can compute the instance buffers with that ordering.
         let instance_buffers = &mut s.0;
-        instance_buffers.clear();
+        let context = &self.context;
 
-        if indices
-            .iter()
-            .map(|i| self.instances.transformations[*i])
-            .all(|t| Mat3::from_cols(t.x.truncate(), t.y.truncate(), t.z.truncate()).is_identity())
+        fill_instance_buffer(
+            context,
+            instance_buffers,
+            "instance_index",
+            &indices.iter().map(|i| *i as u32).collect::<Vec<_>>(),
+        );
+
+        // The animation is applied before the instance transformation, so it cannot be combined with an instance translation
+        if self.instance_animation.is_none()
+            && indices.iter().map(|i| transformations[*i]).all(|t| {
+                Mat3::from_cols(t.x.truncate(), t.y.truncate(), t.z.truncate()).is_identity()
+            })
         {
-            instance_buffers.insert(
-                "instance_translation".to_string(),
-                InstanceBuffer::new_with_data(
-                    &self.context,
-                    &indices
-                        .iter()
-                        .map(|i| self.instances.transformations[*i])
-                        .map(|t| t.w.truncate())
-                        .collect::<Vec<_>>(),
-                ),
+            for name in ["row1", "row2", "row3"] {
+                instance_buffers.remove(name);
+            }
+            fill_instance_buffer(
+                context,
+                instance_buffers,
+                "instance_translation",
+                &indices
+                    .iter()
+                    .map(|i| transformations[*i])
+                    .map(|t| t.w.truncate())
+                    .collect::<Vec<_>>(),
             );
         } else {
             let mut row1 = Vec::new();
             let mut row2 = Vec::new();
             let mut row3 = Vec::new();
-            for transformation in indices.iter().map(|i| self.instances.transformations[*i]) {
+            for transformation in indices.iter().map(|i| transformations[*i]) {
                 row1.push(transformation.row(0));
                 row2.push(transformation.row(1));
                 row3.push(transformation.row(2));
             }
 
-            instance_buffers.insert(
-                "row1".to_string(),
-                InstanceBuffer::new_with_data(&self.context, &row1),
-            );
-            instance_buffers.insert(
-                "row2".to_string(),
-                InstanceBuffer::new_with_data(&self.context, &row2),
-            );
-            instance_buffers.insert(
-                "row3".to_string(),
-                InstanceBuffer::new_with_data(&self.context, &row3),
-            );
+            instance_buffers.remove("instance_translation");
+            fill_instance_buffer(context, instance_buffers, "row1", &row1);
+            fill_instance_buffer(context, instance_buffers, "row2", &row2);
+            fill_instance_buffer(context, instance_buffers, "row3", &row3);
         }
 
         if let Some(texture_transforms) = &self.instances.texture_transformations {
@@ -180,14 +478,21 @@ impl InstancedMesh {
                     texture_transform.z.y,
                 ));
             }
-            instance_buffers.insert(
-                "tex_transform_row1".to_string(),
-                InstanceBuffer::new_with_data(&self.context, &instance_tex_transform1),
+            fill_instance_buffer(
+                context,
+                instance_buffers,
+                "tex_transform_row1",
+                &instance_tex_transform1,
             );
-            instance_buffers.insert(
-                "tex_transform_row2".to_string(),
-                InstanceBuffer::new_with_data(&self.context, &instance_tex_transform2),
+            fill_instance_buffer(
+                context,
+                instance_buffers,
+                "tex_transform_row2",
+                &instance_tex_transform2,
             );
+        } else {
+            instance_buffers.remove("tex_transform_row1");
+            instance_buffers.remove("tex_transform_row2");
         }
         if let Some(instance_colors) = &self.instances.colors {
             // Create the re-ordered color buffer by depth.
@@ -195,10 +500,40 @@ impl InstancedMesh {
                 .iter()
                 .map(|i| instance_colors[*i].to_linear_srgb())
                 .collect::<Vec<_>>();
-            instance_buffers.insert(
-                "instance_color".to_string(),
-                InstanceBuffer::new_with_data(&self.context, &ordered_instance_colors),
+            fill_instance_buffer(
+                context,
+                instance_buffers,
+                "instance_color",
+                &ordered_instance_colors,
             );
+        } else {
+            instance_buffers.remove("instance_color");
+        }
+        if self.instance_animation.is_some() {
+            fill_instance_buffer(
+                context,
+                instance_buffers,
+                "instance_animation",
+                &indices
+                    .iter()
+                    .map(|i| {
+                        vec2(
+                            self.instances
+                                .animation_offsets
+                                .as_ref()
+                                .map(|offsets| offsets[*i])
+                                .unwrap_or(0.0),
+                            self.instances
+                                .animation_speeds
+                                .as_ref()
+                                .map(|speeds| speeds[*i])
+                                .unwrap_or(1.0),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        } else {
+            instance_buffers.remove("instance_animation");
         }
     }
 }
@@ -222,9 +557,9 @@ impl Geometry for InstancedMesh {
     ) {
         // Check if we need a reorder, this only applies to transparent materials.
         if render_states.blend != Blend::Disabled
-            && *camera.position() != self.instance_buffers.read().unwrap().1
+            && Some(*camera.position()) != self.instance_buffers.read().unwrap().1
         {
-            self.update_instance_buffers(Some(camera));
+            self.update_instance_buffers(Some(*camera.position()));
         }
 
         let instance_buffers = &self.instance_buffers.read().unwrap().0;
@@ -236,7 +571,7 @@ impl Geometry for InstancedMesh {
                 return;
             }
         }
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        camera.use_uniform_block(program);
         program.use_uniform("modelMatrix", self.current_transformation);
 
         for attribute_name in [
@@ -247,6 +582,8 @@ impl Geometry for InstancedMesh {
             "tex_transform_row1",
             "tex_transform_row2",
             "instance_color",
+            "instance_index",
+            "instance_animation",
         ] {
             if program.requires_attribute(attribute_name) {
                 program.use_instance_attribute(
@@ -256,6 +593,28 @@ impl Geometry for InstancedMesh {
                 );
             }
         }
+        if let Some(joint_transformations) = &self.instance_joint_transformations {
+            if program.requires_uniform("instanceJointTransformations") {
+                program.use_texture("instanceJointTransformations", joint_transformations);
+            }
+        }
+        if let Some(instance_animation) = &self.instance_animation {
+            if program.requires_uniform("instanceAnimationTexture") {
+                program.use_texture("instanceAnimationTexture", &instance_animation.texture);
+                let mut clips = [vec4(0.0, 0.0, 0.0, 0.0); MAX_INSTANCE_ANIMATION_CLIPS];
+                let mut times = [vec2(0.0, 0.0); MAX_INSTANCE_ANIMATION_CLIPS];
+                for (i, (clip, time)) in instance_animation.playing.iter().enumerate() {
+                    clips[i] = *clip;
+                    times[i] = *time;
+                }
+                program.use_uniform(
+                    "instanceAnimationClipCount",
+                    instance_animation.playing.len() as i32,
+                );
+                program.use_uniform_array("instanceAnimationClips", &clips);
+                program.use_uniform_array("instanceAnimationTimes", &times);
+            }
+        }
         self.base_mesh.draw_instanced(
             program,
             render_states,
@@ -268,7 +627,7 @@ impl Geometry for InstancedMesh {
     fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
         let instance_buffers = &self.instance_buffers.read().unwrap().0;
         format!(
-            "{}{}{}{}{}{}{}{}{}",
+            "#define USE_INSTANCE_INDICES\n{}{}{}{}{}{}{}{}{}{}{}{}",
             if required_attributes.normal {
                 "#define USE_NORMALS\n"
             } else {
@@ -304,7 +663,21 @@ impl Geometry for InstancedMesh {
             } else {
                 ""
             },
+            if self.is_skinned() && self.instance_joint_transformations.is_some() {
+                "#define USE_SKINNING\n#define USE_INSTANCE_SKINNING\n".to_owned()
+            } else {
+                self.base_mesh.skinning_shader_source()
+            },
+            if self.instance_animation.is_some() {
+                format!(
+                    "#define USE_INSTANCE_ANIMATION\n#define MAX_INSTANCE_ANIMATION_CLIPS {}\n",
+                    MAX_INSTANCE_ANIMATION_CLIPS
+                )
+            } else {
+                String::new()
+            },
             include_str!("../../core/shared.frag"),
+            Camera::uniform_block_source(),
             include_str!("shaders/mesh.vert"),
         )
     }
@@ -337,6 +710,15 @@ impl Geometry for InstancedMesh {
         if required_attributes.uv && instance_buffers.contains_key("tex_transform_row1") {
             id |= 0b1u16 << 6;
         }
+        if self.is_skinned() {
+            id |= 0b1u16 << 8;
+            if self.instance_joint_transformations.is_some() {
+                id |= 0b1u16 << 9;
+            }
+        }
+        if self.instance_animation.is_some() {
+            id |= 0b1u16 << 10;
+        }
         id
     }
 
@@ -346,9 +728,24 @@ impl Geometry for InstancedMesh {
         aabb
     }
 
+    fn outputs_instance_id(&self) -> bool {
+        true
+    }
+
     fn animate(&mut self, time: f32) {
         if let Some(animation) = &self.animation {
             self.current_transformation = self.transformation * animation(time);
+        } else if let Some(name) = self
+            .instance_animation
+            .as_ref()
+            .and_then(|instance_animation| instance_animation.clips.first())
+            .map(|clip| clip.name.clone())
+        {
+            self.play_instance_animation_clips(&[AnimationSample {
+                name,
+                time,
+                weight: 1.0,
+            }]);
         }
     }
 
@@ -396,6 +793,10 @@ pub struct Instances {
     pub texture_transformations: Option<Vec<Mat3>>,
     /// Colors multiplied onto the base color of each instance.
     pub colors: Option<Vec<Srgba>>,
+    /// The time offset added to the animation time of each instance, so that each instance plays the animation at its own phase (see [Instances::animation_time] and [InstancedMesh::set_instance_animation]).
+    pub animation_offsets: Option<Vec<f32>>,
+    /// The speed multiplied onto the animation time of each instance, so that each instance plays the animation at its own speed (see [Instances::animation_time] and [InstancedMesh::set_instance_animation]).
+    pub animation_speeds: Option<Vec<f32>>,
 }
 
 impl Instances {
@@ -423,6 +824,14 @@ impl Instances {
         )?;
         buffer_check(Some(self.transformations.len()), "transformations")?;
         buffer_check(self.colors.as_ref().map(|b| b.len()), "colors")?;
+        buffer_check(
+            self.animation_offsets.as_ref().map(|b| b.len()),
+            "animation offsets",
+        )?;
+        buffer_check(
+            self.animation_speeds.as_ref().map(|b| b.len()),
+            "animation speeds",
+        )?;
 
         Ok(())
     }
@@ -431,6 +840,28 @@ impl Instances {
     pub fn count(&self) -> u32 {
         self.transformations.len() as u32
     }
+
+    ///
+    /// Returns the animation time of the instance with the given index given the global animation time,
+    /// ie. the time multiplied by the [Instances::animation_speeds] and offset by the [Instances::animation_offsets].
+    ///
+    pub fn animation_time(&self, index: usize, time: f32) -> f32 {
+        let speed = self
+            .animation_speeds
+            .as_ref()
+            .map(|speeds| speeds[index])
+            .unwrap_or(1.0);
+        let offset = self
+            .animation_offsets
+            .as_ref()
+            .map(|offsets| offsets[index])
+            .unwrap_or(0.0);
+        time * speed + offset
+    }
+
+    pub(in crate::renderer) fn has_animation_times(&self) -> bool {
+        self.animation_offsets.is_some() || self.animation_speeds.is_some()
+    }
 }
 
 impl From<PointCloud> for Instances {
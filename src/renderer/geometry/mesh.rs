@@ -78,6 +78,14 @@ impl Mesh {
         self.animation = Some(Box::new(animation));
     }
 
+    ///
+    /// Sets the animation transformation for the current frame without changing the animation defined by [Self::set_animation],
+    /// which is used again the next time [Geometry::animate] is called.
+    ///
+    pub(in crate::renderer) fn set_animation_transformation(&mut self, animation: Mat4) {
+        self.current_transformation = self.transformation * animation;
+    }
+
     ///
     /// Returns the number of vertices in this mesh.
     ///
@@ -114,6 +122,59 @@ impl Mesh {
             self.base_mesh.normals = Some(VertexBuffer::new_with_data(&self.context, normals));
         }
     }
+
+    ///
+    /// Sets the joint indices and weights for each vertex which are used to deform this mesh by the joint transformations defined by [Self::set_joint_transformations].
+    /// Each vertex is influenced by up to four joints where the weights should sum to one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of joints or weights does not match the number of vertices in the mesh.
+    pub fn set_skinning_attributes(&mut self, joints: &[Vector4<u16>], weights: &[Vec4]) {
+        self.base_mesh
+            .set_skinning_attributes(&self.context, joints, weights);
+    }
+
+    ///
+    /// Sets the transformations of the joints used for skinning, ie. for each joint the global joint transformation multiplied by the inverse bind matrix (see [Skin::joint_transformations]).
+    /// The skinning is applied before any other transformation and only if the joint indices and weights are specified using [Self::set_skinning_attributes].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of joint transformations exceeds [MAX_JOINTS].
+    pub fn set_joint_transformations(&mut self, joint_transformations: &[Mat4]) {
+        self.base_mesh
+            .set_joint_transformations(&self.context, joint_transformations);
+    }
+
+    ///
+    /// Sets the morph targets (also called blend shapes) of this mesh. The position and normal offsets are transfered to the GPU once
+    /// and are afterwards blended on the GPU using the weights given in [Self::set_morph_weights], which are all initialized to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [MAX_MORPH_TARGETS] morph targets are given or if the number of positions or normals in a morph target does not match the number of vertices in the mesh.
+    pub fn set_morph_targets(&mut self, morph_targets: &[MorphTarget]) {
+        self.base_mesh
+            .set_morph_targets(&self.context, morph_targets);
+    }
+
+    ///
+    /// Returns the number of morph targets specified by [Self::set_morph_targets].
+    ///
+    pub fn morph_target_count(&self) -> u32 {
+        self.base_mesh.morph_targets.len() as u32
+    }
+
+    ///
+    /// Sets the weight of each of the morph targets specified by [Self::set_morph_targets].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of weights does not match the number of morph targets.
+    pub fn set_morph_weights(&mut self, weights: &[f32]) {
+        self.base_mesh.set_morph_weights(weights);
+    }
 }
 
 impl<'a> IntoIterator for &'a Mesh {
@@ -132,6 +193,10 @@ impl Geometry for Mesh {
         aabb
     }
 
+    fn outputs_instance_id(&self) -> bool {
+        true
+    }
+
     fn animate(&mut self, time: f32) {
         if let Some(animation) = &self.animation {
             self.current_transformation = self.transformation * animation(time);
@@ -154,7 +219,7 @@ impl Geometry for Mesh {
             }
         }
 
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        camera.use_uniform_block(program);
         program.use_uniform("modelMatrix", self.current_transformation);
 
         self.base_mesh
@@ -163,7 +228,7 @@ impl Geometry for Mesh {
 
     fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
         format!(
-            "{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}{}",
             if required_attributes.normal {
                 "#define USE_NORMALS\n"
             } else {
@@ -184,7 +249,11 @@ impl Geometry for Mesh {
             } else {
                 ""
             },
+            self.base_mesh.skinning_shader_source(),
+            self.base_mesh
+                .morph_targets_shader_source(required_attributes),
             include_str!("../../core/shared.frag"),
+            Camera::uniform_block_source(),
             include_str!("shaders/mesh.vert"),
         )
     }
@@ -203,6 +272,10 @@ impl Geometry for Mesh {
         if required_attributes.color && self.base_mesh.colors.is_some() {
             id |= 0b1u16 << 3;
         }
+        if self.base_mesh.is_skinned() {
+            id |= 0b1u16 << 8;
+        }
+        id |= self.base_mesh.morph_targets_id(required_attributes);
         id
     }
 
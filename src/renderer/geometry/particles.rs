@@ -212,7 +212,7 @@ impl Geometry for ParticleSystem {
 
     fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
         format!(
-            "#define PARTICLES\n{}{}{}{}{}{}{}{}",
+            "#define PARTICLES\n{}{}{}{}{}{}{}{}{}",
             if required_attributes.normal {
                 "#define USE_NORMALS\n"
             } else {
@@ -244,6 +244,7 @@ impl Geometry for ParticleSystem {
                 ""
             },
             include_str!("../../core/shared.frag"),
+            Camera::uniform_block_source(),
             include_str!("shaders/mesh.vert"),
         )
     }
@@ -263,7 +264,7 @@ impl Geometry for ParticleSystem {
                 return;
             }
         }
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        camera.use_uniform_block(program);
         program.use_uniform("modelMatrix", self.transformation);
         program.use_uniform("acceleration", self.acceleration);
         program.use_uniform("time", self.time);
@@ -298,6 +299,10 @@ impl Geometry for ParticleSystem {
         AxisAlignedBoundingBox::INFINITE
     }
 
+    fn outputs_instance_id(&self) -> bool {
+        true
+    }
+
     fn render_with_material(
         &self,
         material: &dyn Material,
@@ -1,5 +1,4 @@
 
-uniform mat4 viewProjection;
 uniform mat4 modelMatrix;
 in vec3 position;
 
@@ -20,13 +19,133 @@ in vec4 row2;
 in vec4 row3;
 #endif
 
+#ifdef USE_INSTANCE_INDICES
+in uint instance_index;
+#endif
+
+#ifdef USE_MORPH_TARGETS
+uniform float morphWeights[MORPH_TARGET_COUNT];
+in vec3 morph_position0;
+#if MORPH_TARGET_COUNT > 1
+in vec3 morph_position1;
+#endif
+#if MORPH_TARGET_COUNT > 2
+in vec3 morph_position2;
+#endif
+#if MORPH_TARGET_COUNT > 3
+in vec3 morph_position3;
+#endif
+#endif
+
+#ifdef USE_SKINNING
+in uvec4 joint_indices;
+in vec4 joint_weights;
+#ifdef USE_INSTANCE_SKINNING
+uniform sampler2D instanceJointTransformations;
+
+mat4 jointTransformation(uint joint)
+{
+    int x = 4 * int(joint);
+    int y = textureSize(instanceJointTransformations, 0).y - 1 - int(instance_index);
+    return mat4(
+        texelFetch(instanceJointTransformations, ivec2(x, y), 0),
+        texelFetch(instanceJointTransformations, ivec2(x + 1, y), 0),
+        texelFetch(instanceJointTransformations, ivec2(x + 2, y), 0),
+        texelFetch(instanceJointTransformations, ivec2(x + 3, y), 0)
+    );
+}
+#else
+layout (std140) uniform JointTransformations
+{
+    mat4 jointTransformations[MAX_JOINTS];
+};
+
+mat4 jointTransformation(uint joint)
+{
+    return jointTransformations[joint];
+}
+#endif
+#endif
+
+#ifdef USE_INSTANCE_ANIMATION
+in vec2 instance_animation;
+uniform sampler2D instanceAnimationTexture;
+uniform vec4 instanceAnimationClips[MAX_INSTANCE_ANIMATION_CLIPS];
+uniform vec2 instanceAnimationTimes[MAX_INSTANCE_ANIMATION_CLIPS];
+uniform int instanceAnimationClipCount;
+
+vec4 instanceAnimationTexel(int x, int row)
+{
+    return texelFetch(instanceAnimationTexture, ivec2(x, textureSize(instanceAnimationTexture, 0).y - 1 - row), 0);
+}
+
+mat4 instanceAnimation()
+{
+    vec3 translation = vec3(0.0);
+    vec4 rotation = vec4(0.0);
+    vec3 scale = vec3(0.0);
+    float totalWeight = 0.0;
+    for (int i = 0; i < instanceAnimationClipCount; i++) {
+        // x: first row, y: sample count, z: duration, w: looping
+        vec4 clip = instanceAnimationClips[i];
+        float weight = instanceAnimationTimes[i].y;
+        float time = instanceAnimationTimes[i].x * instance_animation.y + instance_animation.x;
+        time = clip.w > 0.5 ? mod(time, clip.z) : clamp(time, 0.0, clip.z);
+        float s = clip.z > 0.0 ? time / clip.z * (clip.y - 1.0) : 0.0;
+        int row0 = int(clip.x) + int(floor(s));
+        int row1 = min(row0 + 1, int(clip.x + clip.y) - 1);
+        float f = fract(s);
+
+        vec4 r0 = instanceAnimationTexel(1, row0);
+        vec4 r1 = instanceAnimationTexel(1, row1);
+        if (dot(r0, r1) < 0.0) {
+            r1 = -r1;
+        }
+        vec4 r = normalize(mix(r0, r1, f));
+        if (dot(rotation, r) < 0.0) {
+            r = -r;
+        }
+        translation += weight * mix(instanceAnimationTexel(0, row0).xyz, instanceAnimationTexel(0, row1).xyz, f);
+        rotation += weight * r;
+        scale += weight * mix(instanceAnimationTexel(2, row0).xyz, instanceAnimationTexel(2, row1).xyz, f);
+        totalWeight += weight;
+    }
+    if (totalWeight <= 0.0) {
+        return mat4(1.0);
+    }
+    translation /= totalWeight;
+    scale /= totalWeight;
+    vec4 q = normalize(rotation);
+    mat3 rotationMatrix = mat3(
+        1.0 - 2.0 * (q.y * q.y + q.z * q.z), 2.0 * (q.x * q.y + q.w * q.z), 2.0 * (q.x * q.z - q.w * q.y),
+        2.0 * (q.x * q.y - q.w * q.z), 1.0 - 2.0 * (q.x * q.x + q.z * q.z), 2.0 * (q.y * q.z + q.w * q.x),
+        2.0 * (q.x * q.z + q.w * q.y), 2.0 * (q.y * q.z - q.w * q.x), 1.0 - 2.0 * (q.x * q.x + q.y * q.y)
+    );
+    return mat4(vec4(scale.x * rotationMatrix[0], 0.0), vec4(scale.y * rotationMatrix[1], 0.0), vec4(scale.z * rotationMatrix[2], 0.0), vec4(translation, 1.0));
+}
+#endif
+
 out vec3 pos;
+flat out int instance_id;
 
 #ifdef USE_NORMALS 
 uniform mat4 normalMatrix;
 in vec3 normal;
 out vec3 nor;
 
+#ifdef USE_MORPH_NORMALS
+in vec3 morph_normal0;
+#if MORPH_TARGET_COUNT > 1
+in vec3 morph_normal1;
+#endif
+#if MORPH_TARGET_COUNT > 2
+in vec3 morph_normal2;
+#endif
+#if MORPH_TARGET_COUNT > 3
+in vec3 morph_normal3;
+#endif
+#endif
+
 #ifdef USE_TANGENTS 
 in vec4 tangent;
 out vec3 tang;
@@ -58,6 +177,10 @@ void main()
 {
     // *** POSITION ***
     mat4 local2World = modelMatrix;
+
+#ifdef USE_INSTANCE_ANIMATION
+    local2World *= instanceAnimation();
+#endif
     
 #ifdef USE_INSTANCE_TRANSFORMS
     mat4 transform;
@@ -68,7 +191,29 @@ void main()
     local2World *= transform;
 #endif
 
-    vec4 worldPosition = local2World * vec4(position, 1.);
+#ifdef USE_SKINNING
+    mat4 skinTransform = joint_weights.x * jointTransformation(joint_indices.x)
+        + joint_weights.y * jointTransformation(joint_indices.y)
+        + joint_weights.z * jointTransformation(joint_indices.z)
+        + joint_weights.w * jointTransformation(joint_indices.w);
+    local2World *= skinTransform;
+#endif
+
+    vec3 localPosition = position;
+#ifdef USE_MORPH_TARGETS
+    localPosition += morphWeights[0] * morph_position0;
+#if MORPH_TARGET_COUNT > 1
+    localPosition += morphWeights[1] * morph_position1;
+#endif
+#if MORPH_TARGET_COUNT > 2
+    localPosition += morphWeights[2] * morph_position2;
+#endif
+#if MORPH_TARGET_COUNT > 3
+    localPosition += morphWeights[3] * morph_position3;
+#endif
+#endif
+
+    vec4 worldPosition = local2World * vec4(localPosition, 1.);
     worldPosition /= worldPosition.w;
 #ifdef PARTICLES
     worldPosition.xyz += start_position + start_velocity * time + 0.5 * acceleration * time * time;
@@ -76,18 +221,38 @@ void main()
 #ifdef USE_INSTANCE_TRANSLATIONS 
     worldPosition.xyz += instance_translation;
 #endif
-    gl_Position = viewProjection * worldPosition;
+    gl_Position = cameraViewProjection * worldPosition;
 
     pos = worldPosition.xyz;
 
+    // *** INSTANCE ***
+#ifdef USE_INSTANCE_INDICES
+    instance_id = int(instance_index);
+#else
+    instance_id = gl_InstanceID;
+#endif
+
     // *** NORMAL ***
 #ifdef USE_NORMALS 
-#ifdef USE_INSTANCE_TRANSFORMS
+#if defined(USE_INSTANCE_TRANSFORMS) || defined(USE_SKINNING)
     mat3 normalMat = mat3(transpose(inverse(local2World)));
 #else
     mat3 normalMat = mat3(normalMatrix);
 #endif
-    nor = normalize(normalMat * normal);
+    vec3 localNormal = normal;
+#ifdef USE_MORPH_NORMALS
+    localNormal += morphWeights[0] * morph_normal0;
+#if MORPH_TARGET_COUNT > 1
+    localNormal += morphWeights[1] * morph_normal1;
+#endif
+#if MORPH_TARGET_COUNT > 2
+    localNormal += morphWeights[2] * morph_normal2;
+#endif
+#if MORPH_TARGET_COUNT > 3
+    localNormal += morphWeights[3] * morph_normal3;
+#endif
+#endif
+    nor = normalize(normalMat * localNormal);
 
 #ifdef USE_TANGENTS 
     tang = normalize(normalMat * tangent.xyz);
@@ -1,4 +1,3 @@
-uniform mat4 viewProjection;
 uniform vec3 eye;
 uniform mat4 transformation;
 uniform vec3 direction;
@@ -11,11 +10,13 @@ in vec2 uv_coordinate;
 out vec2 uvs;
 out vec4 col;
 out vec3 pos;
+flat out int instance_id;
 
 void main()
 {
     uvs = uv_coordinate;
     col = vec4(1.0);
+    instance_id = gl_InstanceID;
 
     vec3 z = normalize(eye - center);
     vec3 y = direction;
@@ -37,5 +38,5 @@ void main()
                 center.x, center.y, center.z, 1.0);
     vec4 world_pos = instanced_transform * transformation * vec4(position, 1.);
     pos = world_pos.xyz / world_pos.w;
-    gl_Position = viewProjection * world_pos;
+    gl_Position = cameraViewProjection * world_pos;
 }
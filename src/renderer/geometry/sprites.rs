@@ -85,7 +85,7 @@ impl Sprites {
 
     fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
         program.use_uniform("eye", camera.position());
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        camera.use_uniform_block(program);
         program.use_uniform("transformation", self.transformation);
         program.use_vertex_attribute("position", &self.position_buffer);
         program.use_vertex_attribute("uv_coordinate", &self.uv_buffer);
@@ -127,7 +127,11 @@ impl Geometry for Sprites {
     }
 
     fn vertex_shader_source(&self, _required_attributes: FragmentAttributes) -> String {
-        include_str!("shaders/sprites.vert").to_owned()
+        format!(
+            "{}{}",
+            Camera::uniform_block_source(),
+            include_str!("shaders/sprites.vert")
+        )
     }
 
     fn id(&self, _required_attributes: FragmentAttributes) -> u16 {
@@ -165,4 +169,8 @@ impl Geometry for Sprites {
     fn aabb(&self) -> AxisAlignedBoundingBox {
         AxisAlignedBoundingBox::INFINITE
     }
+
+    fn outputs_instance_id(&self) -> bool {
+        true
+    }
 }
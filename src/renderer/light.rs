@@ -12,6 +12,9 @@ macro_rules! impl_light_body {
         fn use_uniforms(&self, program: &Program, i: u32) {
             self.$inner().use_uniforms(program, i)
         }
+        fn uniform_data(&self) -> Vec<Vec4> {
+            self.$inner().uniform_data()
+        }
         fn id(&self) -> u8 {
             self.$inner().id()
         }
@@ -67,6 +70,96 @@ impl Default for Attenuation {
     }
 }
 
+///
+/// Specifies how the shadow map lookup is filtered, see [ShadowSettings].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShadowFilter {
+    /// A single comparison with the shadow map which results in hard and possibly pixelated shadow edges.
+    Hard,
+    /// Percentage closer filtering which averages the comparisons in a regular grid of `kernel_size` x `kernel_size` texels.
+    Pcf {
+        /// The width and height of the filter kernel in texels. Should be an odd number.
+        kernel_size: u32,
+    },
+    /// Percentage closer filtering which averages 16 comparisons distributed on a Poisson disk.
+    PoissonPcf {
+        /// The radius of the Poisson disk in texels.
+        radius: f32,
+    },
+    /// Percentage closer soft shadows which estimates the size of the penumbra from the distance between the shadow caster and the receiver,
+    /// so the shadows are sharp close to the caster and softer further away.
+    Pcss {
+        /// The size of the light relative to the size of the area covered by the shadow map.
+        light_size: f32,
+    },
+}
+
+impl ShadowFilter {
+    fn mode(&self) -> u8 {
+        match self {
+            Self::Hard => 0,
+            Self::Pcf { .. } => 1,
+            Self::PoissonPcf { .. } => 2,
+            Self::Pcss { .. } => 3,
+        }
+    }
+
+    fn parameter(&self) -> f32 {
+        match self {
+            Self::Hard => 0.0,
+            Self::Pcf { kernel_size } => *kernel_size as f32,
+            Self::PoissonPcf { radius } => *radius,
+            Self::Pcss { light_size } => *light_size,
+        }
+    }
+}
+
+///
+/// Settings which control the appearance of the shadows cast by a light.
+/// Each [ShadowFilter] results in a different shader, whereas the other parameters can be changed without recompiling the shader.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowSettings {
+    /// A bias which is subtracted from the depth of a surface before comparing with the depth in the shadow map to avoid shadow acne.
+    /// The bias is increased up to ten times for surfaces which are almost parallel to the light direction.
+    pub depth_bias: f32,
+    /// The distance a surface is moved along its normal before looking up in the shadow map, which also helps avoid shadow acne.
+    pub normal_offset_bias: f32,
+    /// How the shadow map lookup is filtered.
+    pub filter: ShadowFilter,
+}
+
+impl ShadowSettings {
+    ///
+    /// The filter mode which is inserted as a literal into the shader source and therefore must be folded into the id of the light.
+    ///
+    pub(crate) fn filter_mode(&self) -> u8 {
+        self.filter.mode()
+    }
+
+    ///
+    /// The depth bias, normal offset bias and filter parameter which should be sent to the shader as a `vec3`.
+    ///
+    pub(crate) fn parameters(&self) -> Vec3 {
+        vec3(
+            self.depth_bias,
+            self.normal_offset_bias,
+            self.filter.parameter(),
+        )
+    }
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            depth_bias: 0.005,
+            normal_offset_bias: 0.0,
+            filter: ShadowFilter::PoissonPcf { radius: 1.0 },
+        }
+    }
+}
+
 /// Represents a light source.
 pub trait Light {
     /// The fragment shader source for calculating this lights contribution to the color in a fragment.
@@ -76,9 +169,27 @@ pub trait Light {
     /// This function should return the color contribution for this light on the surface with the given surface parameters.
     fn shader_source(&self, i: u32) -> String;
 
-    /// Should bind the uniforms that is needed for calculating this lights contribution to the color in [Light::shader_source].
+    ///
+    /// Should bind the uniforms that is needed for calculating this lights contribution to the color in [Light::shader_source],
+    /// except the data returned by [Light::uniform_data].
+    ///
+    /// **Note:** The data returned by [Light::uniform_data] is not sent here, but in the `Lights` uniform block declared by [lights_shader_source],
+    /// which is sent by the render functions, for example [render_with_material](crate::renderer::render_with_material), or by [use_lights_uniforms].
+    ///
     fn use_uniforms(&self, program: &Program, i: u32);
 
+    ///
+    /// Returns the data of this light which is sent to the shader in a uniform buffer shared by all the lights, see [use_lights_uniforms].
+    /// Since the uniform buffer is only updated when the data changes, this is more efficient than sending the data in [Light::use_uniforms] for each draw call.
+    /// In the shader source, the data can be accessed as `lightData[LIGHT{}_OFFSET + j]`, where `{}` is replaced with the number i given to [Light::shader_source]
+    /// and `j` is the index into the returned list. The length of the list must only depend on the [Light::id].
+    ///
+    /// The default implementation returns an empty list, ie. all data is sent in [Light::use_uniforms].
+    ///
+    fn uniform_data(&self) -> Vec<Vec4> {
+        Vec::new()
+    }
+
     ///
     /// Returns a unique ID for each variation of the shader source returned from `Light::shader_source`.
     ///
@@ -119,6 +230,9 @@ impl<T: Light> Light for std::sync::Arc<std::sync::RwLock<T>> {
     fn use_uniforms(&self, program: &Program, i: u32) {
         self.read().unwrap().use_uniforms(program, i)
     }
+    fn uniform_data(&self) -> Vec<Vec4> {
+        self.read().unwrap().uniform_data()
+    }
     fn id(&self) -> u8 {
         self.read().unwrap().id()
     }
@@ -126,7 +240,10 @@ impl<T: Light> Light for std::sync::Arc<std::sync::RwLock<T>> {
 
 ///
 /// Returns shader source code with the function `calculate_lighting` which calculate the lighting contribution for the given lights and the given [LightingModel].
-/// Use this if you want to implement a custom [Material](crate::renderer::Material) but use the default lighting calculations.
+/// Use this if you want to implement a custom [Material](crate::renderer::Material) but use the default lighting calculations
+/// and send the uniforms needed by the lights using [use_lights_uniforms].
+/// The data returned by [Light::uniform_data] is declared in the `Lights` uniform block, which is also sent by the render functions,
+/// so calling [Light::use_uniforms] for each light is enough when rendering with for example [render_with_material](crate::renderer::render_with_material).
 ///
 /// The shader function has the following signature:
 /// ```no_rust
@@ -137,6 +254,17 @@ pub fn lights_shader_source(lights: &[&dyn Light], lighting_model: LightingModel
     let mut shader_source = lighting_model_shader(lighting_model).to_string();
     shader_source.push_str(include_str!("../core/shared.frag"));
     shader_source.push_str(include_str!("light/shaders/light_shared.frag"));
+    let mut offset = 0;
+    for (i, light) in lights.iter().enumerate() {
+        shader_source.push_str(&format!("#define LIGHT{}_OFFSET {}\n", i, offset));
+        offset += light.uniform_data().len();
+    }
+    if offset > 0 {
+        shader_source.push_str(&format!(
+            "layout (std140) uniform Lights\n{{\n    vec4 lightData[{}];\n}};\n",
+            offset
+        ));
+    }
     let mut dir_fun = String::new();
     for (i, light) in lights.iter().enumerate() {
         shader_source.push_str(&light.shader_source(i as u32));
@@ -157,6 +285,36 @@ pub fn lights_shader_source(lights: &[&dyn Light], lighting_model: LightingModel
     shader_source
 }
 
+///
+/// Sends the uniforms needed by the given lights to the given program, ie. the data returned by [Light::uniform_data] for all of the lights
+/// in a uniform buffer shared between programs and the rest of the uniforms using [Light::use_uniforms].
+/// Use this together with [lights_shader_source] when implementing a custom [Material](crate::renderer::Material).
+///
+pub fn use_lights_uniforms(program: &Program, lights: &[&dyn Light]) {
+    use_lights_uniform_block(program, lights);
+    for (i, light) in lights.iter().enumerate() {
+        light.use_uniforms(program, i as u32);
+    }
+}
+
+///
+/// Sends the data returned by [Light::uniform_data] for all of the lights in the `Lights` uniform block if the program declares it (see [lights_shader_source]).
+/// The data is only sent to the GPU when it has changed, so this is cheap to call for each draw call.
+///
+pub(crate) fn use_lights_uniform_block(program: &Program, lights: &[&dyn Light]) {
+    if !program.requires_uniform_block("Lights") {
+        return;
+    }
+    let data = lights
+        .iter()
+        .flat_map(|light| light.uniform_data())
+        .flat_map(|v| [v.x, v.y, v.z, v.w])
+        .collect::<Vec<_>>();
+    if !data.is_empty() {
+        program.use_shared_uniform_block("Lights", &data);
+    }
+}
+
 fn shadow_matrix(camera: &Camera) -> Mat4 {
     let bias_matrix = crate::Mat4::new(
         0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.5, 0.5, 0.5, 1.0,
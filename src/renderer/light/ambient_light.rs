@@ -47,7 +47,7 @@ impl Light for AmbientLight {
                 uniform samplerCube irradianceMap;
                 uniform samplerCube prefilterMap;
                 uniform sampler2D brdfLUT;
-                uniform vec3 ambientColor;
+                #define ambientColor lightData[LIGHT{}_OFFSET].xyz
     
                 vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                 {{
@@ -75,17 +75,17 @@ impl Light for AmbientLight {
                     return (diffuse + specular) * occlusion * ambientColor;
                 }}
             
-            ", i)
+            ", i, i)
         } else {
             format!(
                 "
-                    uniform vec3 ambientColor;
+                    #define ambientColor lightData[LIGHT{}_OFFSET].xyz
                     vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
                         return occlusion * ambientColor * mix(surface_color, vec3(0.0), metallic);
                     }}
                 
-                ", i)
+                ", i, i)
         }
     }
     fn use_uniforms(&self, program: &Program, _i: u32) {
@@ -94,10 +94,10 @@ impl Light for AmbientLight {
             program.use_texture_cube("prefilterMap", &environment.prefilter_map);
             program.use_texture("brdfLUT", &environment.brdf_map);
         }
-        program.use_uniform(
-            "ambientColor",
-            self.color.to_linear_srgb().truncate() * self.intensity,
-        );
+    }
+
+    fn uniform_data(&self) -> Vec<Vec4> {
+        vec![(self.color.to_linear_srgb().truncate() * self.intensity).extend(0.0)]
     }
 
     fn id(&self) -> u8 {
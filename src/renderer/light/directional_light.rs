@@ -2,20 +2,65 @@ use crate::core::*;
 use crate::renderer::light::*;
 use crate::renderer::*;
 
+///
+/// The maximum number of cascades supported by [DirectionalLight::generate_cascaded_shadow_map].
+///
+pub const MAX_SHADOW_CASCADES: u32 = 4;
+
+///
+/// Settings for a cascaded shadow map, see [DirectionalLight::generate_cascaded_shadow_map].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CascadedShadowSettings {
+    /// The number of cascades, ie. the number of depth slices the view frustum is split into. Must be between 1 and [MAX_SHADOW_CASCADES].
+    pub cascade_count: u32,
+    /// Controls the distribution of the split distances between the near and far plane of the view frustum.
+    /// A value of 0 gives a uniform distribution and a value of 1 gives a logarithmic distribution where the cascades close to the camera are much smaller than those far away.
+    pub split_distribution: f32,
+    /// The fraction (between 0 and 1) of each cascade depth range in which the shadow is blended with the next cascade to hide the seams between cascades.
+    pub blend_fraction: f32,
+    /// The maximum distance from the camera where shadows are rendered. If None, the far plane of the camera is used.
+    pub max_distance: Option<f32>,
+}
+
+impl Default for CascadedShadowSettings {
+    fn default() -> Self {
+        Self {
+            cascade_count: 4,
+            split_distribution: 0.75,
+            blend_fraction: 0.1,
+            max_distance: None,
+        }
+    }
+}
+
+struct ShadowCascades {
+    texture: DepthTexture2DArray,
+    matrices: Vec<Mat4>,
+    splits: Vec<f32>,
+    eye: Vec3,
+    view_direction: Vec3,
+    blend_fraction: f32,
+}
+
 ///
 /// A light which shines in the given direction.
-/// The light will cast shadows if you [generate a shadow map](DirectionalLight::generate_shadow_map).
+/// The light will cast shadows if you [generate a shadow map](DirectionalLight::generate_shadow_map)
+/// or [generate a cascaded shadow map](DirectionalLight::generate_cascaded_shadow_map).
 ///
 pub struct DirectionalLight {
     context: Context,
     shadow_texture: Option<DepthTexture2D>,
     shadow_matrix: Mat4,
+    shadow_cascades: Option<ShadowCascades>,
     /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high intensity light sources like the sun.
     pub intensity: f32,
     /// The base color of the light.
     pub color: Srgba,
     /// The direction the light shines.
     pub direction: Vec3,
+    /// Settings which control the appearance of the shadow, only used if a shadow map has been generated.
+    pub shadow_settings: ShadowSettings,
 }
 
 impl DirectionalLight {
@@ -30,19 +75,23 @@ impl DirectionalLight {
             context: context.clone(),
             shadow_matrix: Mat4::identity(),
             shadow_texture: None,
+            shadow_cascades: None,
             intensity,
             color,
             direction: *direction,
+            shadow_settings: ShadowSettings::default(),
         }
     }
 
     ///
     /// Clear the shadow map, effectively disable the shadow.
-    /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [DirectionalLight::generate_shadow_map].
+    /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [DirectionalLight::generate_shadow_map]
+    /// or [DirectionalLight::generate_cascaded_shadow_map].
     ///
     pub fn clear_shadow_map(&mut self) {
         self.shadow_texture = None;
         self.shadow_matrix = Mat4::identity();
+        self.shadow_cascades = None;
     }
 
     ///
@@ -50,6 +99,7 @@ impl DirectionalLight {
     /// It is recomended that the texture size is power of 2.
     /// If the shadows are too low resolution (the edges between shadow and non-shadow are pixelated) try to increase the texture size
     /// and/or split the scene by creating another light source with same parameters and let the two light sources shines on different parts of the scene.
+    /// For large scenes, consider using [DirectionalLight::generate_cascaded_shadow_map] instead.
     ///
     pub fn generate_shadow_map(
         &mut self,
@@ -115,6 +165,166 @@ impl DirectionalLight {
             .unwrap();
         self.shadow_texture = Some(shadow_texture);
         self.shadow_matrix = shadow_matrix(&shadow_camera);
+        self.shadow_cascades = None;
+    }
+
+    ///
+    /// Generate a cascaded shadow map which is used to simulate shadows from the directional light onto the geometries given as input.
+    /// The view frustum of the given camera is split into a number of depth slices (cascades) as specified by the settings
+    /// and a shadow map is rendered for each slice into a layer of a [DepthTexture2DArray].
+    /// This gives high resolution shadows close to the camera and lower resolution shadows far away from the camera,
+    /// so it is well suited for large scenes. The shadow map needs to be regenerated whenever the camera changes.
+    /// It is recomended that the texture size is power of 2.
+    ///
+    pub fn generate_cascaded_shadow_map(
+        &mut self,
+        camera: &Camera,
+        texture_size: u32,
+        settings: CascadedShadowSettings,
+        geometries: impl IntoIterator<Item = impl Geometry> + Clone,
+    ) {
+        let cascade_count = settings.cascade_count.clamp(1, MAX_SHADOW_CASCADES);
+        let direction = self.direction.normalize();
+        let up = compute_up_direction(direction);
+        let right = direction.cross(up).normalize();
+        let up = right.cross(direction).normalize();
+
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for geometry in geometries.clone() {
+            aabb.expand_with_aabb(&geometry.aabb());
+        }
+        if aabb.is_empty() {
+            return;
+        }
+
+        // Split distances
+        let z_near = camera.z_near().max(0.001);
+        let z_far = settings
+            .max_distance
+            .map(|d| d.min(camera.z_far()))
+            .unwrap_or_else(|| camera.z_far())
+            .max(z_near + 0.001);
+        let lambda = settings.split_distribution.clamp(0.0, 1.0);
+        let mut splits = vec![z_near];
+        for i in 1..=cascade_count {
+            let f = i as f32 / cascade_count as f32;
+            let logarithmic = z_near * (z_far / z_near).powf(f);
+            let uniform = z_near + (z_far - z_near) * f;
+            splits.push(lambda * logarithmic + (1.0 - lambda) * uniform);
+        }
+
+        // Corners of the view frustum on the near and far plane
+        let inverse = (camera.projection() * camera.view()).invert().unwrap();
+        let mut near_corners = Vec::with_capacity(4);
+        let mut far_corners = Vec::with_capacity(4);
+        for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+            let near = inverse * vec4(x, y, -1.0, 1.0);
+            let far = inverse * vec4(x, y, 1.0, 1.0);
+            near_corners.push(near.truncate() / near.w);
+            far_corners.push(far.truncate() / far.w);
+        }
+        let camera_z_near = camera.z_near();
+        let camera_z_far = camera.z_far();
+        let corner_at = |i: usize, z: f32| {
+            let t = (z - camera_z_near) / (camera_z_far - camera_z_near);
+            near_corners[i] + (far_corners[i] - near_corners[i]) * t
+        };
+
+        let mut texture = DepthTexture2DArray::new::<f32>(
+            &self.context,
+            texture_size,
+            texture_size,
+            cascade_count,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let depth_material = DepthMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let viewport = Viewport::new_at_origo(texture_size, texture_size);
+        let mut matrices = Vec::with_capacity(cascade_count as usize);
+        for cascade in 0..cascade_count {
+            let corners = (0..4)
+                .flat_map(|i| {
+                    [
+                        corner_at(i, splits[cascade as usize]),
+                        corner_at(i, splits[cascade as usize + 1]),
+                    ]
+                })
+                .collect::<Vec<_>>();
+
+            // Fit a bounding sphere around the slice to get a stable shadow when the camera rotates
+            let center =
+                corners.iter().fold(vec3(0.0, 0.0, 0.0), |acc, c| acc + *c) / corners.len() as f32;
+            let radius = corners
+                .iter()
+                .map(|c| c.distance(center))
+                .fold(0.0f32, f32::max)
+                .max(0.001);
+
+            // Snap the center to the texel grid to avoid shimmering edges when the camera moves
+            let texel_size = 2.0 * radius / texture_size as f32;
+            let x = (center.dot(right) / texel_size).floor() * texel_size;
+            let y = (center.dot(up) / texel_size).floor() * texel_size;
+            let center = right * x + up * y + direction * center.dot(direction);
+
+            // Move the light camera back so that all geometries between the light and the slice cast shadows
+            let back = aabb_corners(&aabb)
+                .iter()
+                .map(|c| (center - *c).dot(direction))
+                .fold(radius, f32::max);
+            let front = aabb_corners(&aabb)
+                .iter()
+                .map(|c| (*c - center).dot(direction))
+                .fold(0.0f32, f32::max)
+                .min(radius);
+            let position = center - direction * back;
+            let shadow_camera = Camera::new_orthographic(
+                viewport,
+                position,
+                center,
+                up,
+                2.0 * radius,
+                0.0,
+                back + front,
+            );
+            texture
+                .as_depth_target(cascade)
+                .clear(ClearState::default())
+                .write::<RendererError>(|| {
+                    for geometry in geometries
+                        .clone()
+                        .into_iter()
+                        .filter(|g| shadow_camera.in_frustum(&g.aabb()))
+                    {
+                        render_with_material(
+                            &self.context,
+                            &shadow_camera,
+                            &geometry,
+                            &depth_material,
+                            &[],
+                        );
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            matrices.push(shadow_matrix(&shadow_camera));
+        }
+
+        self.shadow_texture = None;
+        self.shadow_matrix = Mat4::identity();
+        self.shadow_cascades = Some(ShadowCascades {
+            texture,
+            matrices,
+            splits: splits[1..].to_vec(),
+            eye: camera.position(),
+            view_direction: camera.view_direction(),
+            blend_fraction: settings.blend_fraction.clamp(0.0, 1.0),
+        });
     }
 
     ///
@@ -123,55 +333,130 @@ impl DirectionalLight {
     pub fn shadow_map(&self) -> Option<&DepthTexture2D> {
         self.shadow_texture.as_ref()
     }
+
+    ///
+    /// Returns a reference to the cascaded shadow map if it has been generated.
+    /// Each layer in the texture array contains the shadow map for one cascade.
+    ///
+    pub fn cascaded_shadow_map(&self) -> Option<&DepthTexture2DArray> {
+        self.shadow_cascades.as_ref().map(|c| &c.texture)
+    }
+}
+
+fn aabb_corners(aabb: &AxisAlignedBoundingBox) -> [Vec3; 8] {
+    let (min, max) = (aabb.min(), aabb.max());
+    [
+        vec3(min.x, min.y, min.z),
+        vec3(max.x, min.y, min.z),
+        vec3(min.x, max.y, min.z),
+        vec3(max.x, max.y, min.z),
+        vec3(min.x, min.y, max.z),
+        vec3(max.x, min.y, max.z),
+        vec3(min.x, max.y, max.z),
+        vec3(max.x, max.y, max.z),
+    ]
 }
 
 impl Light for DirectionalLight {
     fn shader_source(&self, i: u32) -> String {
-        if self.shadow_texture.is_some() {
+        if self.shadow_cascades.is_some() {
+            format!(
+                "
+                    uniform sampler2DArray shadowMaps{};
+                    uniform mat4 shadowMVPs{}[{}];
+                    uniform float cascadeSplits{}[{}];
+                    uniform int cascadeCount{};
+                    uniform float cascadeBlend{};
+                    uniform vec3 cascadeEye{};
+                    uniform vec3 cascadeViewDirection{};
+                    uniform vec3 shadowParameters{};
+
+                    #define color{} lightData[LIGHT{}_OFFSET].xyz
+                    #define direction{} lightData[LIGHT{}_OFFSET + 1].xyz
+
+                    vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                    {{
+                        float view_depth = dot(position - cascadeEye{}, cascadeViewDirection{});
+                        return calculate_light(color{}, -direction{}, surface_color, view_direction, normal, metallic, roughness)
+                            * calculate_cascaded_shadow(-direction{}, normal, shadowMaps{}, shadowMVPs{}, cascadeSplits{}, cascadeCount{}, cascadeBlend{}, view_depth, position, {}, shadowParameters{});
+                    }}
+
+                ", i, i, MAX_SHADOW_CASCADES, i, MAX_SHADOW_CASCADES, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, self.shadow_settings.filter_mode(), i)
+        } else if self.shadow_texture.is_some() {
             format!(
                 "
                     uniform sampler2D shadowMap{};
                     uniform mat4 shadowMVP{};
+                    uniform vec3 shadowParameters{};
 
-                    uniform vec3 color{};
-                    uniform vec3 direction{};
+                    #define color{} lightData[LIGHT{}_OFFSET].xyz
+                    #define direction{} lightData[LIGHT{}_OFFSET + 1].xyz
 
                     vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
                         return calculate_light(color{}, -direction{}, surface_color, view_direction, normal, metallic, roughness)
-                            * calculate_shadow(-direction{}, normal, shadowMap{}, shadowMVP{}, position);
+                            * calculate_shadow(-direction{}, normal, shadowMap{}, shadowMVP{}, position, {}, shadowParameters{});
                     }}
 
-                ", i, i, i, i, i, i, i, i, i, i)
+                ", i, i, i, i, i, i, i, i, i, i, i, i, i, self.shadow_settings.filter_mode(), i)
         } else {
             format!(
                 "
-                    uniform vec3 color{};
-                    uniform vec3 direction{};
+                    #define color{} lightData[LIGHT{}_OFFSET].xyz
+                    #define direction{} lightData[LIGHT{}_OFFSET + 1].xyz
 
                     vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
                         return calculate_light(color{}, -direction{}, surface_color, view_direction, normal, metallic, roughness);
                     }}
 
-                ", i, i, i, i, i)
+                ", i, i, i, i, i, i, i)
         }
     }
     fn use_uniforms(&self, program: &Program, i: u32) {
+        if let Some(ref cascades) = self.shadow_cascades {
+            let mut matrices = cascades.matrices.clone();
+            matrices.resize(MAX_SHADOW_CASCADES as usize, Mat4::identity());
+            let mut splits = cascades.splits.clone();
+            splits.resize(MAX_SHADOW_CASCADES as usize, f32::MAX);
+            program.use_depth_texture_array(&format!("shadowMaps{}", i), &cascades.texture);
+            program.use_uniform_array(&format!("shadowMVPs{}", i), &matrices);
+            program.use_uniform_array(&format!("cascadeSplits{}", i), &splits);
+            program.use_uniform(
+                &format!("cascadeCount{}", i),
+                cascades.matrices.len() as i32,
+            );
+            program.use_uniform(&format!("cascadeBlend{}", i), cascades.blend_fraction);
+            program.use_uniform(&format!("cascadeEye{}", i), cascades.eye);
+            program.use_uniform(
+                &format!("cascadeViewDirection{}", i),
+                cascades.view_direction,
+            );
+        }
+        if self.shadow_cascades.is_some() || self.shadow_texture.is_some() {
+            program.use_uniform(
+                &format!("shadowParameters{}", i),
+                self.shadow_settings.parameters(),
+            );
+        }
         if let Some(ref tex) = self.shadow_texture {
             program.use_depth_texture(&format!("shadowMap{}", i), tex);
             program.use_uniform(&format!("shadowMVP{}", i), self.shadow_matrix);
         }
-        program.use_uniform(
-            &format!("color{}", i),
-            self.color.to_linear_srgb().truncate() * self.intensity,
-        );
-        program.use_uniform(&format!("direction{}", i), self.direction.normalize());
+    }
+
+    fn uniform_data(&self) -> Vec<Vec4> {
+        vec![
+            (self.color.to_linear_srgb().truncate() * self.intensity).extend(0.0),
+            self.direction.normalize().extend(0.0),
+        ]
     }
 
     fn id(&self) -> u8 {
-        if self.shadow_texture.is_some() {
-            0b1u8 << 7 | 0b10u8
+        if self.shadow_cascades.is_some() {
+            0b1u8 << 7 | self.shadow_settings.filter_mode() << 4 | 0b1000u8
+        } else if self.shadow_texture.is_some() {
+            0b1u8 << 7 | self.shadow_settings.filter_mode() << 4 | 0b10u8
         } else {
             0b1u8 << 7 | 0b11u8
         }
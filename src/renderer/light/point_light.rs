@@ -1,10 +1,16 @@
 use crate::core::*;
+use crate::renderer::light::*;
 use crate::renderer::*;
 
 ///
 /// A light which shines from the given position in all directions.
+/// The light will cast shadows if you [generate a shadow map](PointLight::generate_shadow_map).
 ///
 pub struct PointLight {
+    context: Context,
+    shadow_texture: Option<DepthTextureCubeMap>,
+    shadow_z_near: f32,
+    shadow_z_far: f32,
     /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high intensity light sources like the sun.
     pub intensity: f32,
     /// The base color of the light.
@@ -13,63 +19,208 @@ pub struct PointLight {
     pub position: Vec3,
     /// The [Attenuation] of the light.
     pub attenuation: Attenuation,
+    /// Settings which control the appearance of the shadow, only used if a shadow map has been generated.
+    pub shadow_settings: ShadowSettings,
 }
 
 impl PointLight {
     /// Constructs a new point light.
     pub fn new(
-        _context: &Context,
+        context: &Context,
         intensity: f32,
         color: Srgba,
         position: &Vec3,
         attenuation: Attenuation,
     ) -> PointLight {
         PointLight {
+            context: context.clone(),
+            shadow_texture: None,
+            shadow_z_near: 0.0,
+            shadow_z_far: 0.0,
             intensity,
             color,
             position: *position,
             attenuation,
+            shadow_settings: ShadowSettings::default(),
         }
     }
+
+    ///
+    /// Clear the shadow map, effectively disable the shadow.
+    /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [PointLight::generate_shadow_map].
+    ///
+    pub fn clear_shadow_map(&mut self) {
+        self.shadow_texture = None;
+        self.shadow_z_near = 0.0;
+        self.shadow_z_far = 0.0;
+    }
+
+    ///
+    /// Generate a shadow map which is used to simulate shadows from the point light onto the geometries given as input.
+    /// The shadow map is a cube map, so the geometries are rendered once for each of the six sides of the cube.
+    /// It is recomended that the texture size is power of 2.
+    /// If the shadows are too low resolution (the edges between shadow and non-shadow are pixelated) try to increase the texture size.
+    ///
+    pub fn generate_shadow_map(
+        &mut self,
+        texture_size: u32,
+        geometries: impl IntoIterator<Item = impl Geometry> + Clone,
+    ) {
+        let position = self.position;
+        let viewport = Viewport::new_at_origo(texture_size, texture_size);
+
+        let mut z_far = 0.0f32;
+        let mut z_near = f32::MAX;
+        for geometry in geometries.clone() {
+            let aabb = geometry.aabb();
+            if !aabb.is_empty() {
+                z_far = z_far.max(aabb.distance_max(&position));
+                z_near = z_near.min(aabb.distance(&position));
+            }
+        }
+        if z_far <= 0.0 {
+            // Nothing casts a shadow, so make sure an old shadow map is not used
+            self.clear_shadow_map();
+            return;
+        }
+        let z_near = z_near.max(0.01).min(0.5 * z_far);
+
+        let mut shadow_texture = DepthTextureCubeMap::new::<f32>(
+            &self.context,
+            texture_size,
+            texture_size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let depth_material = DepthMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for side in CubeMapSide::iter() {
+            let shadow_camera = Camera::new_perspective(
+                viewport,
+                position,
+                position + side.direction(),
+                side.up(),
+                degrees(90.0),
+                z_near,
+                z_far,
+            );
+            shadow_texture
+                .as_depth_target(side)
+                .clear(ClearState::default())
+                .write::<RendererError>(|| {
+                    for geometry in geometries
+                        .clone()
+                        .into_iter()
+                        .filter(|g| shadow_camera.in_frustum(&g.aabb()))
+                    {
+                        render_with_material(
+                            &self.context,
+                            &shadow_camera,
+                            &geometry,
+                            &depth_material,
+                            &[],
+                        );
+                    }
+                    Ok(())
+                })
+                .unwrap();
+        }
+        self.shadow_texture = Some(shadow_texture);
+        self.shadow_z_near = z_near;
+        self.shadow_z_far = z_far;
+    }
+
+    ///
+    /// Returns a reference to the shadow map if it has been generated.
+    ///
+    pub fn shadow_map(&self) -> Option<&DepthTextureCubeMap> {
+        self.shadow_texture.as_ref()
+    }
 }
 
 impl Light for PointLight {
     fn shader_source(&self, i: u32) -> String {
-        format!(
-        "
-            uniform vec3 color{};
-            uniform vec3 attenuation{};
-            uniform vec3 position{};
+        if self.shadow_texture.is_some() {
+            format!(
+            "
+                uniform samplerCube shadowMap{};
+                uniform vec2 shadowNearFar{};
+                uniform vec3 shadowParameters{};
+
+                #define color{} lightData[LIGHT{}_OFFSET].xyz
+                #define attenuation{} lightData[LIGHT{}_OFFSET + 1].xyz
+                #define position{} lightData[LIGHT{}_OFFSET + 2].xyz
+
+                vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                {{
+                    vec3 light_direction = position{} - position;
+                    float distance = length(light_direction);
+                    light_direction = light_direction / distance;
 
-            vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
-            {{
-                vec3 light_direction = position{} - position;
-                float distance = length(light_direction);
-                light_direction = light_direction / distance;
+                    vec3 light_color = attenuate(color{}, attenuation{}, distance);
+                    return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness)
+                        * calculate_shadow_cube(light_direction, normal, shadowMap{}, shadowNearFar{}, position - position{}, {}, shadowParameters{});
+                }}
+            
+            ", i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, self.shadow_settings.filter_mode(), i)
+        } else {
+            format!(
+            "
+                #define color{} lightData[LIGHT{}_OFFSET].xyz
+                #define attenuation{} lightData[LIGHT{}_OFFSET + 1].xyz
+                #define position{} lightData[LIGHT{}_OFFSET + 2].xyz
 
-                vec3 light_color = attenuate(color{}, attenuation{}, distance);
-                return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
-            }}
-        
-        ", i, i, i, i, i, i, i)
+                vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                {{
+                    vec3 light_direction = position{} - position;
+                    float distance = length(light_direction);
+                    light_direction = light_direction / distance;
+
+                    vec3 light_color = attenuate(color{}, attenuation{}, distance);
+                    return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
+                }}
+            
+            ", i, i, i, i, i, i, i, i, i, i)
+        }
     }
     fn use_uniforms(&self, program: &Program, i: u32) {
-        program.use_uniform(
-            &format!("color{}", i),
-            self.color.to_linear_srgb().truncate() * self.intensity,
-        );
-        program.use_uniform(
-            &format!("attenuation{}", i),
-            vec3(
+        if let Some(ref tex) = self.shadow_texture {
+            program.use_depth_texture_cube(&format!("shadowMap{}", i), tex);
+            program.use_uniform(
+                &format!("shadowNearFar{}", i),
+                vec2(self.shadow_z_near, self.shadow_z_far),
+            );
+            program.use_uniform(
+                &format!("shadowParameters{}", i),
+                self.shadow_settings.parameters(),
+            );
+        }
+    }
+
+    fn uniform_data(&self) -> Vec<Vec4> {
+        vec![
+            (self.color.to_linear_srgb().truncate() * self.intensity).extend(0.0),
+            vec4(
                 self.attenuation.constant,
                 self.attenuation.linear,
                 self.attenuation.quadratic,
+                0.0,
             ),
-        );
-        program.use_uniform(&format!("position{}", i), self.position);
+            self.position.extend(0.0),
+        ]
     }
 
     fn id(&self) -> u8 {
-        0b1u8 << 7 | 0b100u8
+        if self.shadow_texture.is_some() {
+            0b1u8 << 7 | self.shadow_settings.filter_mode() << 4 | 0b111u8
+        } else {
+            0b1u8 << 7 | 0b100u8
+        }
     }
 }
@@ -146,9 +146,48 @@ vec3 attenuate(vec3 light_color, vec3 attenuation, float distance)
     return light_color / max(1.0, att);
 }
 
-float is_visible(vec3 lightDirection, vec3 normal, sampler2D shadowMap, vec4 shadow_coord, vec2 offset)
+// Shadow filter modes, must match the values in ShadowFilter::mode
+#define SHADOW_FILTER_HARD 0
+#define SHADOW_FILTER_PCF 1
+#define SHADOW_FILTER_POISSON 2
+#define SHADOW_FILTER_PCSS 3
+
+const vec2 POISSON_DISK[16] = vec2[](
+    vec2( -0.94201624, -0.39906216 ),
+    vec2( 0.94558609, -0.76890725 ),
+    vec2( -0.094184101, -0.92938870 ),
+    vec2( 0.34495938, 0.29387760 ),
+    vec2( -0.91588581, 0.45771432 ),
+    vec2( -0.81544232, -0.87912464 ),
+    vec2( -0.38277543, 0.27676845 ),
+    vec2( 0.97484398, 0.75648379 ),
+    vec2( 0.44323325, -0.97511554 ),
+    vec2( 0.53742981, -0.47373420 ),
+    vec2( -0.26496911, -0.41893023 ),
+    vec2( 0.79197514, 0.19090188 ),
+    vec2( -0.24188840, 0.99706507 ),
+    vec2( -0.81409955, 0.91437590 ),
+    vec2( 0.19984126, 0.78641367 ),
+    vec2( 0.14383161, -0.14100790 )
+);
+
+// Adjust shadow bias based on surface normal and light direction
+float shadow_bias(vec3 lightDirection, vec3 normal, float depth_bias)
+{
+    return max(10.0 * depth_bias * (1.0 - dot(normal, lightDirection)), depth_bias);
+}
+
+float penumbra_size(float depth, float blocker_depth, float light_size)
+{
+    return light_size * (depth - blocker_depth) / max(blocker_depth, 0.0001);
+}
+
+//
+// 2D shadow maps
+//
+
+float is_visible(sampler2D shadowMap, vec2 uv, float depth)
 {
-    vec2 uv = (shadow_coord.xy + offset)/shadow_coord.w;
     if(uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
         return 1.0;
     }
@@ -156,27 +195,220 @@ float is_visible(vec3 lightDirection, vec3 normal, sampler2D shadowMap, vec4 sha
     if(shadow_cast_distance > 0.999) {
         return 1.0;
     }
-    // Adjust shadow bias based on surface normal and light direction
-    float bias = max(0.05 * (1.0 - dot(normal, lightDirection)), 0.005);
-    float true_distance = (shadow_coord.z - bias)/shadow_coord.w;
-    return shadow_cast_distance > true_distance ? 1.0 : 0.0;
+    return shadow_cast_distance > depth ? 1.0 : 0.0;
+}
+
+float filter_shadow(sampler2D shadowMap, vec2 uv, float depth, int filter_mode, float filter_parameter)
+{
+    vec2 texel_size = 1.0 / vec2(textureSize(shadowMap, 0));
+    if (filter_mode == SHADOW_FILTER_PCF) {
+        int r = int(filter_parameter) / 2;
+        float visibility = 0.0;
+        for (int x = -r; x <= r; x++) {
+            for (int y = -r; y <= r; y++) {
+                visibility += is_visible(shadowMap, uv + vec2(float(x), float(y)) * texel_size, depth);
+            }
+        }
+        return visibility / float((2 * r + 1) * (2 * r + 1));
+    }
+    float radius = filter_parameter;
+    if (filter_mode == SHADOW_FILTER_PCSS) {
+        float blocker_depth = 0.0;
+        float blocker_count = 0.0;
+        for (int i = 0; i < 16; i++) {
+            vec2 sample_uv = uv + POISSON_DISK[i] * filter_parameter;
+            float d = texture(shadowMap, sample_uv).x;
+            if (d < depth) {
+                blocker_depth += d;
+                blocker_count += 1.0;
+            }
+        }
+        if (blocker_count < 0.5) {
+            return 1.0;
+        }
+        radius = max(penumbra_size(depth, blocker_depth / blocker_count, filter_parameter) / texel_size.x, 1.0);
+    }
+    if (filter_mode == SHADOW_FILTER_POISSON || filter_mode == SHADOW_FILTER_PCSS) {
+        float visibility = 0.0;
+        for (int i = 0; i < 16; i++) {
+            visibility += is_visible(shadowMap, uv + POISSON_DISK[i] * radius * texel_size, depth);
+        }
+        return visibility / 16.0;
+    }
+    return is_visible(shadowMap, uv, depth);
+}
+
+// The shadow parameters are the depth bias, the normal offset bias and the filter parameter.
+float calculate_shadow(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec3 position, int filter_mode, vec3 shadow_parameters)
+{
+    vec4 shadow_coord = shadowMVP * vec4(position + normal * shadow_parameters.y, 1.);
+    float bias = shadow_bias(lightDirection, normal, shadow_parameters.x);
+    float depth = (shadow_coord.z - bias)/shadow_coord.w;
+    return filter_shadow(shadowMap, shadow_coord.xy / shadow_coord.w, depth, filter_mode, shadow_parameters.z);
+}
+
+//
+// 2D shadow map arrays (used for cascaded shadow maps)
+//
+
+float is_visible(sampler2DArray shadowMaps, int layer, vec2 uv, float depth)
+{
+    if(uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        return 1.0;
+    }
+    float shadow_cast_distance = texture(shadowMaps, vec3(uv, float(layer))).x;
+    if(shadow_cast_distance > 0.999) {
+        return 1.0;
+    }
+    return shadow_cast_distance > depth ? 1.0 : 0.0;
+}
+
+float filter_shadow(sampler2DArray shadowMaps, int layer, vec2 uv, float depth, int filter_mode, float filter_parameter)
+{
+    vec2 texel_size = 1.0 / vec2(textureSize(shadowMaps, 0).xy);
+    if (filter_mode == SHADOW_FILTER_PCF) {
+        int r = int(filter_parameter) / 2;
+        float visibility = 0.0;
+        for (int x = -r; x <= r; x++) {
+            for (int y = -r; y <= r; y++) {
+                visibility += is_visible(shadowMaps, layer, uv + vec2(float(x), float(y)) * texel_size, depth);
+            }
+        }
+        return visibility / float((2 * r + 1) * (2 * r + 1));
+    }
+    float radius = filter_parameter;
+    if (filter_mode == SHADOW_FILTER_PCSS) {
+        float blocker_depth = 0.0;
+        float blocker_count = 0.0;
+        for (int i = 0; i < 16; i++) {
+            vec2 sample_uv = uv + POISSON_DISK[i] * filter_parameter;
+            float d = texture(shadowMaps, vec3(sample_uv, float(layer))).x;
+            if (d < depth) {
+                blocker_depth += d;
+                blocker_count += 1.0;
+            }
+        }
+        if (blocker_count < 0.5) {
+            return 1.0;
+        }
+        radius = max(penumbra_size(depth, blocker_depth / blocker_count, filter_parameter) / texel_size.x, 1.0);
+    }
+    if (filter_mode == SHADOW_FILTER_POISSON || filter_mode == SHADOW_FILTER_PCSS) {
+        float visibility = 0.0;
+        for (int i = 0; i < 16; i++) {
+            visibility += is_visible(shadowMaps, layer, uv + POISSON_DISK[i] * radius * texel_size, depth);
+        }
+        return visibility / 16.0;
+    }
+    return is_visible(shadowMaps, layer, uv, depth);
 }
 
-float calculate_shadow(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec3 position)
+float calculate_shadow(vec3 lightDirection, vec3 normal, sampler2DArray shadowMaps, int layer, mat4 shadowMVP, vec3 position, int filter_mode, vec3 shadow_parameters)
 {
-    vec4 shadow_coord = shadowMVP * vec4(position, 1.);
-    float visibility = 0.0;
-    vec2 poissonDisk[4] = vec2[](
-                                 vec2( -0.94201624, -0.39906216 ),
-                                 vec2( 0.94558609, -0.76890725 ),
-                                 vec2( -0.094184101, -0.92938870 ),
-                                 vec2( 0.34495938, 0.29387760 )
-                                 );
-    for (int i=0;i<4;i++)
+    vec4 shadow_coord = shadowMVP * vec4(position + normal * shadow_parameters.y, 1.);
+    float bias = shadow_bias(lightDirection, normal, shadow_parameters.x);
+    float depth = (shadow_coord.z - bias)/shadow_coord.w;
+    return filter_shadow(shadowMaps, layer, shadow_coord.xy / shadow_coord.w, depth, filter_mode, shadow_parameters.z);
+}
+
+float calculate_cascaded_shadow(vec3 lightDirection, vec3 normal, sampler2DArray shadowMaps, mat4 shadowMVPs[4], float splits[4], int cascade_count, float blend, float view_depth, vec3 position, int filter_mode, vec3 shadow_parameters)
+{
+    int cascade = cascade_count;
+    for (int i = 0; i < cascade_count; i++)
     {
-        visibility += is_visible(lightDirection, normal, shadowMap, shadow_coord, poissonDisk[i] * 0.001f);
+        if (view_depth < splits[i]) {
+            cascade = i;
+            break;
+        }
+    }
+    if (cascade >= cascade_count) {
+        return 1.0;
+    }
+    float visibility = calculate_shadow(lightDirection, normal, shadowMaps, cascade, shadowMVPs[cascade], position, filter_mode, shadow_parameters);
+
+    // Blend with the next cascade close to the split to hide the seam
+    float start = cascade == 0 ? 0.0 : splits[cascade - 1];
+    float blend_start = splits[cascade] - blend * (splits[cascade] - start);
+    if (blend > 0.0 && view_depth > blend_start) {
+        float next_visibility = 1.0;
+        if (cascade + 1 < cascade_count) {
+            next_visibility = calculate_shadow(lightDirection, normal, shadowMaps, cascade + 1, shadowMVPs[cascade + 1], position, filter_mode, shadow_parameters);
+        }
+        visibility = mix(visibility, next_visibility, smoothstep(blend_start, splits[cascade], view_depth));
+    }
+    return visibility;
+}
+
+//
+// Cube shadow maps (used for point lights)
+//
+
+float is_visible(samplerCube shadowMap, vec2 near_far, vec3 light_to_position, float distance)
+{
+    float shadow_cast_depth = texture(shadowMap, light_to_position).x;
+    if(shadow_cast_depth > 0.999) {
+        return 1.0;
+    }
+    // Convert the non-linear depth to the distance along the major axis of the cube map side
+    float n = near_far.x;
+    float f = near_far.y;
+    float shadow_cast_distance = 2.0 * n * f / (f + n - (2.0 * shadow_cast_depth - 1.0) * (f - n));
+    return shadow_cast_distance > distance ? 1.0 : 0.0;
+}
+
+float calculate_shadow_cube(vec3 lightDirection, vec3 normal, samplerCube shadowMap, vec2 near_far, vec3 light_to_position, int filter_mode, vec3 shadow_parameters)
+{
+    light_to_position += normal * shadow_parameters.y;
+    vec3 abs_dir = abs(light_to_position);
+    float true_distance = max(abs_dir.x, max(abs_dir.y, abs_dir.z));
+    float distance = true_distance * (1.0 - shadow_bias(lightDirection, normal, shadow_parameters.x));
+
+    // Offsets are applied in the plane orthogonal to the sample direction and scaled to the size of a texel
+    vec3 dir = normalize(light_to_position);
+    vec3 tangent = normalize(cross(abs(dir.y) < 0.999 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0), dir));
+    vec3 bitangent = cross(dir, tangent);
+    float texel_size = 2.0 * true_distance / float(textureSize(shadowMap, 0).x);
+
+    if (filter_mode == SHADOW_FILTER_PCF) {
+        int r = int(shadow_parameters.z) / 2;
+        float visibility = 0.0;
+        for (int x = -r; x <= r; x++) {
+            for (int y = -r; y <= r; y++) {
+                vec3 offset = (tangent * float(x) + bitangent * float(y)) * texel_size;
+                visibility += is_visible(shadowMap, near_far, light_to_position + offset, distance);
+            }
+        }
+        return visibility / float((2 * r + 1) * (2 * r + 1));
+    }
+    float radius = shadow_parameters.z;
+    if (filter_mode == SHADOW_FILTER_PCSS) {
+        float n = near_far.x;
+        float f = near_far.y;
+        float blocker_distance = 0.0;
+        float blocker_count = 0.0;
+        for (int i = 0; i < 16; i++) {
+            vec2 o = POISSON_DISK[i] * shadow_parameters.z * 2.0 * true_distance;
+            float d = texture(shadowMap, light_to_position + tangent * o.x + bitangent * o.y).x;
+            float blocker = 2.0 * n * f / (f + n - (2.0 * d - 1.0) * (f - n));
+            if (d < 0.999 && blocker < distance) {
+                blocker_distance += blocker;
+                blocker_count += 1.0;
+            }
+        }
+        if (blocker_count < 0.5) {
+            return 1.0;
+        }
+        radius = max(penumbra_size(distance, blocker_distance / blocker_count, shadow_parameters.z) * 2.0 * true_distance / texel_size, 1.0);
+    }
+    if (filter_mode == SHADOW_FILTER_POISSON || filter_mode == SHADOW_FILTER_PCSS) {
+        float visibility = 0.0;
+        for (int i = 0; i < 16; i++) {
+            vec2 o = POISSON_DISK[i] * radius * texel_size;
+            visibility += is_visible(shadowMap, near_far, light_to_position + tangent * o.x + bitangent * o.y, distance);
+        }
+        return visibility / 16.0;
     }
-    return visibility * 0.25;
+    return is_visible(shadowMap, near_far, light_to_position, distance);
 }
 
 vec3 ImportanceSampleGGX(vec2 Xi, vec3 N, float roughness)
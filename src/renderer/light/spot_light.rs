@@ -22,6 +22,8 @@ pub struct SpotLight {
     pub cutoff: Radians,
     /// The [Attenuation] of the light.
     pub attenuation: Attenuation,
+    /// Settings which control the appearance of the shadow, only used if a shadow map has been generated.
+    pub shadow_settings: ShadowSettings,
 }
 
 impl SpotLight {
@@ -45,6 +47,7 @@ impl SpotLight {
             cutoff: cutoff.into(),
             attenuation,
             shadow_matrix: Mat4::identity(),
+            shadow_settings: ShadowSettings::default(),
         }
     }
 
@@ -145,12 +148,13 @@ impl Light for SpotLight {
                 "
                     uniform sampler2D shadowMap{};
                     uniform mat4 shadowMVP{};
+                    uniform vec3 shadowParameters{};
 
-                    uniform vec3 color{};
-                    uniform vec3 attenuation{};
-                    uniform vec3 position{};
-                    uniform float cutoff{};
-                    uniform vec3 direction{};
+                    #define color{} lightData[LIGHT{}_OFFSET].xyz
+                    #define attenuation{} lightData[LIGHT{}_OFFSET + 1].xyz
+                    #define position{} lightData[LIGHT{}_OFFSET + 2].xyz
+                    #define cutoff{} lightData[LIGHT{}_OFFSET + 3].w
+                    #define direction{} lightData[LIGHT{}_OFFSET + 3].xyz
                     vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
                         vec3 light_direction = position{} - position;
@@ -165,20 +169,20 @@ impl Light for SpotLight {
                             vec3 light_color = attenuate(color{}, attenuation{}, distance);
                             result = calculate_light(light_color, light_direction, surface_color, view_direction, normal,
                                 metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
-                            result *= calculate_shadow(light_direction, normal, shadowMap{}, shadowMVP{}, position);
+                            result *= calculate_shadow(light_direction, normal, shadowMap{}, shadowMVP{}, position, {}, shadowParameters{});
                         }}
                         return result;
                     }}
 
-                ", i, i, i, i, i, i, i, i, i, i, i, i, i, i, i)
+                ", i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, self.shadow_settings.filter_mode(), i)
         } else {
             format!(
                 "
-                    uniform vec3 color{};
-                    uniform vec3 attenuation{};
-                    uniform vec3 position{};
-                    uniform float cutoff{};
-                    uniform vec3 direction{};
+                    #define color{} lightData[LIGHT{}_OFFSET].xyz
+                    #define attenuation{} lightData[LIGHT{}_OFFSET + 1].xyz
+                    #define position{} lightData[LIGHT{}_OFFSET + 2].xyz
+                    #define cutoff{} lightData[LIGHT{}_OFFSET + 3].w
+                    #define direction{} lightData[LIGHT{}_OFFSET + 3].xyz
                     vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
                         vec3 light_direction = position{} - position;
@@ -197,34 +201,37 @@ impl Light for SpotLight {
                         return result;
                     }}
 
-                ", i, i, i, i, i, i, i, i, i, i, i)
+                ", i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i)
         }
     }
     fn use_uniforms(&self, program: &Program, i: u32) {
         if let Some(ref tex) = self.shadow_texture {
             program.use_depth_texture(&format!("shadowMap{}", i), tex);
             program.use_uniform(&format!("shadowMVP{}", i), self.shadow_matrix);
+            program.use_uniform(
+                &format!("shadowParameters{}", i),
+                self.shadow_settings.parameters(),
+            );
         }
-        program.use_uniform(
-            &format!("color{}", i),
-            self.color.to_linear_srgb().truncate() * self.intensity,
-        );
-        program.use_uniform(
-            &format!("attenuation{}", i),
-            vec3(
+    }
+
+    fn uniform_data(&self) -> Vec<Vec4> {
+        vec![
+            (self.color.to_linear_srgb().truncate() * self.intensity).extend(0.0),
+            vec4(
                 self.attenuation.constant,
                 self.attenuation.linear,
                 self.attenuation.quadratic,
+                0.0,
             ),
-        );
-        program.use_uniform(&format!("position{}", i), self.position);
-        program.use_uniform(&format!("direction{}", i), self.direction.normalize());
-        program.use_uniform(&format!("cutoff{}", i), self.cutoff.0);
+            self.position.extend(0.0),
+            self.direction.normalize().extend(self.cutoff.0),
+        ]
     }
 
     fn id(&self) -> u8 {
         if self.shadow_texture.is_some() {
-            0b1u8 << 7 | 0b101u8
+            0b1u8 << 7 | self.shadow_settings.filter_mode() << 4 | 0b101u8
         } else {
             0b1u8 << 7 | 0b110u8
         }
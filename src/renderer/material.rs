@@ -70,10 +70,25 @@ mod skybox_material;
 #[doc(inline)]
 pub(in crate::renderer) use skybox_material::*;
 
+mod pick_material;
+pub(in crate::renderer) use pick_material::*;
+
+mod selection_material;
+pub(in crate::renderer) use selection_material::*;
+
+mod error_material;
+pub(in crate::renderer) use error_material::*;
+
 mod isosurface_material;
 #[doc(inline)]
 pub use isosurface_material::*;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod hot_reload_material;
+#[cfg(not(target_arch = "wasm32"))]
+#[doc(inline)]
+pub use hot_reload_material::*;
+
 use std::{ops::Deref, sync::Arc};
 
 ///
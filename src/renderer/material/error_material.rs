@@ -0,0 +1,33 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Used internally as a fallback when the program for a material or effect fails to compile, see [ProgramCache::set_error_fallback].
+/// Renders the geometry in magenta.
+///
+#[derive(Clone, Copy)]
+pub(in crate::renderer) struct ErrorMaterial;
+
+impl Material for ErrorMaterial {
+    fn id(&self) -> u16 {
+        0b1u16 << 15 | 0b1u16 << 8
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("shaders/error_material.frag").to_owned()
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes::NONE
+    }
+
+    fn use_uniforms(&self, _program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {}
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
@@ -0,0 +1,181 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::SystemTime;
+
+static NEXT_ID: AtomicU16 = AtomicU16::new(0);
+
+///
+/// A material meant for development which wraps another [Material] but loads the fragment shader source from a file,
+/// so that the shader can be edited without restarting the application.
+/// Call [HotReloadMaterial::update] from the render loop to reload the source when the file has changed.
+/// All other properties, for example the uniforms, the fragment attributes and the render states, are given by the wrapped material.
+///
+/// **Note:** The content of the file is used as the entire fragment shader source, ie. the source code for the lights is not added.
+///
+pub struct HotReloadMaterial<M: Material> {
+    /// The wrapped material.
+    pub material: M,
+    source: HotReloadSource,
+}
+
+impl<M: Material> HotReloadMaterial<M> {
+    ///
+    /// Creates a new material which uses the fragment shader source in the file at the given path together with the given material.
+    /// Returns an error if the file cannot be read or if the source fails to compile.
+    ///
+    pub fn new(
+        context: &Context,
+        path: impl AsRef<Path>,
+        material: M,
+    ) -> Result<Self, RendererError> {
+        let source = HotReloadSource::new(context, path, material.fragment_attributes())?;
+        Ok(Self { material, source })
+    }
+
+    ///
+    /// Checks if the file has been modified since it was last loaded and if so, reloads the fragment shader source.
+    /// If the new source compiles and links with the vertex shader of a [Mesh], the programs compiled with the previous source by this material are removed from [Context::programs],
+    /// so they are compiled with the new source the next time they are used, and `true` is returned.
+    /// Otherwise the compilation error is returned and the last working source and programs are kept.
+    /// The error is only returned once for each modification of the file.
+    ///
+    pub fn update(&mut self) -> Result<bool, RendererError> {
+        self.source.update(self.material.fragment_attributes())
+    }
+
+    ///
+    /// Returns the path to the file containing the fragment shader source.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.source.path
+    }
+
+    ///
+    /// Returns the last working fragment shader source.
+    ///
+    pub fn source(&self) -> &str {
+        &self.source.source
+    }
+}
+
+impl<M: Material> Material for HotReloadMaterial<M> {
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        self.source.source.clone()
+    }
+
+    fn id(&self) -> u16 {
+        self.source.id
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        self.material.fragment_attributes()
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
+        self.material.use_uniforms(program, camera, lights)
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.material.render_states()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.material.material_type()
+    }
+}
+
+///
+/// A fragment shader source loaded from a file which is reloaded when the file is modified, used by [HotReloadMaterial] and [HotReloadEffect].
+///
+pub(in crate::renderer) struct HotReloadSource {
+    pub id: u16,
+    pub path: PathBuf,
+    pub source: String,
+    context: Context,
+    modified: Option<SystemTime>,
+}
+
+impl HotReloadSource {
+    pub fn new(
+        context: &Context,
+        path: impl AsRef<Path>,
+        fragment_attributes: FragmentAttributes,
+    ) -> Result<Self, RendererError> {
+        let path = path.as_ref().to_path_buf();
+        let modified = modified_time(&path)?;
+        let source = read_source(&path)?;
+        validate_source(context, &path, &source, fragment_attributes)?;
+        Ok(Self {
+            id: next_id(),
+            path,
+            source,
+            context: context.clone(),
+            modified,
+        })
+    }
+
+    pub fn update(
+        &mut self,
+        fragment_attributes: FragmentAttributes,
+    ) -> Result<bool, RendererError> {
+        let modified = modified_time(&self.path)?;
+        if modified == self.modified {
+            return Ok(false);
+        }
+        self.modified = modified;
+        let source = read_source(&self.path)?;
+        if source == self.source {
+            return Ok(false);
+        }
+        validate_source(&self.context, &self.path, &source, fragment_attributes)?;
+        self.source = source;
+
+        // The program ids used in the render functions are the geometry id followed by the material or effect id
+        let id = self.id.to_le_bytes();
+        self.context
+            .programs
+            .write()
+            .unwrap()
+            .retain(|program_id| program_id.get(2..4) != Some(&id[..]));
+        Ok(true)
+    }
+}
+
+// Each hot reload material and effect has its own id, so its programs are not shared with the wrapped material or effect or with other hot reload materials and effects
+fn next_id() -> u16 {
+    0b1u16 << 15 | 0b1u16 << 14 | (NEXT_ID.fetch_add(1, Ordering::Relaxed) & 0x3FFF)
+}
+
+fn modified_time(path: &Path) -> Result<Option<SystemTime>, RendererError> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| RendererError::ShaderSourceIo(path.display().to_string(), e))?;
+    Ok(metadata.modified().ok())
+}
+
+fn read_source(path: &Path) -> Result<String, RendererError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| RendererError::ShaderSourceIo(path.display().to_string(), e))
+}
+
+// Links the source with the vertex shader of a mesh, so that errors in the interface between the shaders are also found before the working programs are removed
+fn validate_source(
+    context: &Context,
+    path: &Path,
+    source: &str,
+    fragment_attributes: FragmentAttributes,
+) -> Result<(), RendererError> {
+    let vertex_shader_source =
+        Mesh::new(context, &CpuMesh::square()).vertex_shader_source(fragment_attributes);
+    Program::from_source(context, &vertex_shader_source, source)
+        .map(|_| ())
+        .map_err(|e| {
+            shader_error(
+                e,
+                ("geometry", &vertex_shader_source),
+                (&path.display().to_string(), source),
+                &[],
+            )
+        })
+}
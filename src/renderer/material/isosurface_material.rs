@@ -47,9 +47,7 @@ impl Material for IsosurfaceMaterial {
     fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
         camera.tone_mapping.use_uniforms(program);
         camera.color_mapping.use_uniforms(program);
-        for (i, light) in lights.iter().enumerate() {
-            light.use_uniforms(program, i as u32);
-        }
+        use_lights_uniforms(program, lights);
         program.use_uniform("cameraPosition", camera.position());
         program.use_uniform("surfaceColor", self.color.to_linear_srgb());
         program.use_uniform("metallic", self.metallic);
@@ -220,9 +220,7 @@ impl Material for PhysicalMaterial {
         camera.color_mapping.use_uniforms(program);
         if !lights.is_empty() {
             program.use_uniform_if_required("cameraPosition", camera.position());
-            for (i, light) in lights.iter().enumerate() {
-                light.use_uniforms(program, i as u32);
-            }
+            use_lights_uniforms(program, lights);
             program.use_uniform("metallic", self.metallic);
             program.use_uniform_if_required("roughness", self.roughness);
             if program.requires_uniform("albedoTexture") {
@@ -0,0 +1,69 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Used internally for picking, see [pick_with_info].
+/// Renders the distance to the camera together with the geometry, instance and triangle index into the first color attachment,
+/// the geometric normal into the second and the uv coordinates (if required) into the third.
+///
+#[derive(Clone)]
+pub(in crate::renderer) struct PickMaterial {
+    /// The index of the geometry that is rendered with this material.
+    pub geometry_index: u32,
+    /// Whether or not to output uv coordinates.
+    pub uv: bool,
+    /// Whether or not the geometry outputs the instance id, otherwise the instance index is set to -1.
+    pub instance_id: bool,
+    /// The maximum distance from the camera, which is used to normalize the distance.
+    pub max_distance: f32,
+}
+
+impl Material for PickMaterial {
+    fn id(&self) -> u16 {
+        let id = if self.uv {
+            0b1u16 << 15 | 0b1001u16
+        } else {
+            0b1u16 << 15 | 0b1000u16
+        };
+        if self.instance_id {
+            id
+        } else {
+            id | 0b1u16 << 9
+        }
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        format!(
+            "{}{}{}",
+            if self.uv { "#define USE_UVS\n" } else { "" },
+            if self.instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/pick_material.frag")
+        )
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes {
+            position: true,
+            uv: self.uv,
+            ..FragmentAttributes::NONE
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform("eye", camera.position());
+        program.use_uniform("maxDistance", self.max_distance);
+        program.use_uniform("geometryIndex", self.geometry_index as f32);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
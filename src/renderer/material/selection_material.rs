@@ -0,0 +1,52 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Used internally for selection, see [select].
+/// Renders the object index plus one and the instance index into an unsigned integer color target.
+///
+#[derive(Clone)]
+pub(in crate::renderer) struct SelectionMaterial {
+    /// The index of the object that is rendered with this material.
+    pub object_index: u32,
+    /// Whether or not the geometry outputs the instance id, otherwise the instance index is set to `u32::MAX`.
+    pub instance_id: bool,
+}
+
+impl Material for SelectionMaterial {
+    fn id(&self) -> u16 {
+        if self.instance_id {
+            0b1u16 << 15 | 0b1010u16
+        } else {
+            0b1u16 << 15 | 0b1u16 << 9 | 0b1010u16
+        }
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        format!(
+            "{}{}",
+            if self.instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/selection_material.frag")
+        )
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes::NONE
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform("objectIndex", self.object_index);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
@@ -0,0 +1,49 @@
+
+uniform vec3 eye;
+uniform float maxDistance;
+uniform float geometryIndex;
+
+in vec3 pos;
+#ifdef USE_INSTANCE_ID
+flat in int instance_id;
+#endif
+
+#ifdef USE_UVS
+in vec2 uvs;
+#endif
+
+layout (location = 0) out vec4 outIds;
+layout (location = 1) out vec4 outNormal;
+layout (location = 2) out vec4 outUv;
+
+void main()
+{
+    float dist = distance(pos, eye) / maxDistance;
+
+#ifdef GL_ES
+    // The primitive id is not available in fragment shaders in OpenGL ES 3.0 / WebGL 2
+    float triangle = -1.0;
+#else
+    float triangle = float(gl_PrimitiveID);
+#endif
+#ifdef USE_INSTANCE_ID
+    float instance = float(instance_id);
+#else
+    // The geometry does not output the instance id
+    float instance = -1.0;
+#endif
+    outIds = vec4(dist, geometryIndex, instance, triangle);
+
+    // The geometric normal facing towards the eye
+    vec3 normal = normalize(cross(dFdx(pos), dFdy(pos)));
+    if (dot(normal, eye - pos) < 0.0) {
+        normal = -normal;
+    }
+    outNormal = vec4(normal, 1.0);
+
+#ifdef USE_UVS
+    outUv = vec4(uvs, 0.0, 1.0);
+#else
+    outUv = vec4(0.0);
+#endif
+}
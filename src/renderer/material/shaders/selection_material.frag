@@ -0,0 +1,19 @@
+uniform uint objectIndex;
+
+#ifdef USE_INSTANCE_ID
+flat in int instance_id;
+#endif
+
+layout (location = 0) out uvec4 outIds;
+
+void main()
+{
+    // Zero is reserved for pixels where no object is visible
+#ifdef USE_INSTANCE_ID
+    uint instance = uint(instance_id);
+#else
+    // The geometry does not output the instance id
+    uint instance = 0xFFFFFFFFu;
+#endif
+    outIds = uvec4(objectIndex + 1u, instance, 0u, 0u);
+}
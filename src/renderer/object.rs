@@ -30,6 +30,18 @@ mod instanced_model;
 #[doc(inline)]
 pub use instanced_model::*;
 
+mod skin;
+#[doc(inline)]
+pub use skin::*;
+
+mod morph_animation;
+#[doc(inline)]
+pub use morph_animation::*;
+
+mod animation_player;
+#[doc(inline)]
+pub use animation_player::*;
+
 mod voxel_grid;
 #[doc(inline)]
 pub use voxel_grid::*;
@@ -0,0 +1,346 @@
+use crate::renderer::*;
+
+///
+/// Defines what happens when the playback of an [AnimationClip] reaches the end of the clip.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// The clip is played once and then stays at the last frame.
+    Once,
+    /// The clip starts over from the beginning.
+    #[default]
+    Repeat,
+    /// The clip is played backwards to the beginning and then forward again.
+    PingPong,
+}
+
+///
+/// An animation clip played by an [AnimationPlayer], ie. a reference to the animations with the given name
+/// (see for example [Model::animations]) together with the playback settings.
+///
+#[derive(Clone, Debug)]
+pub struct AnimationClip {
+    /// The name of the animation.
+    pub name: Option<String>,
+    /// The weight of this clip when blended with the other active clips.
+    pub weight: f32,
+    /// The playback speed, where 1.0 is normal speed and a negative value plays the clip backwards.
+    pub speed: f32,
+    /// What happens when the playback reaches the end of the clip. Only used if the [AnimationClip::duration] is specified.
+    pub loop_mode: LoopMode,
+    /// The duration of the clip. If `None`, the playback time is not clamped or looped by the player.
+    pub duration: Option<f32>,
+}
+
+impl AnimationClip {
+    ///
+    /// Creates a new clip for the animation with the given name with weight 1, normal speed and without a duration.
+    ///
+    pub fn new(name: Option<&str>) -> Self {
+        Self {
+            name: name.map(|n| n.to_owned()),
+            weight: 1.0,
+            speed: 1.0,
+            loop_mode: LoopMode::Repeat,
+            duration: None,
+        }
+    }
+}
+
+///
+/// The evaluated state of an active [AnimationClip], ie. the time in the animation to evaluate and the weight the result should have in the blend.
+///
+#[derive(Clone, Debug)]
+pub struct AnimationSample {
+    /// The name of the animation.
+    pub name: Option<String>,
+    /// The time in the animation.
+    pub time: f32,
+    /// The blend weight including any cross-fade.
+    pub weight: f32,
+}
+
+struct ActiveClip {
+    clip: AnimationClip,
+    local_time: f32,
+    elapsed: f32,
+    fade_in: Option<f32>,
+    fade_out: Option<(f32, f32)>,
+}
+
+impl ActiveClip {
+    fn new(clip: AnimationClip, fade_in: Option<f32>) -> Self {
+        Self {
+            clip,
+            local_time: 0.0,
+            elapsed: 0.0,
+            fade_in,
+            fade_out: None,
+        }
+    }
+
+    fn animation_time(&self) -> f32 {
+        if let Some(duration) = self.clip.duration.filter(|d| *d > 0.0) {
+            match self.clip.loop_mode {
+                LoopMode::Once => self.local_time.clamp(0.0, duration),
+                LoopMode::Repeat => self.local_time.rem_euclid(duration),
+                LoopMode::PingPong => {
+                    let t = self.local_time.rem_euclid(2.0 * duration);
+                    if t > duration {
+                        2.0 * duration - t
+                    } else {
+                        t
+                    }
+                }
+            }
+        } else {
+            self.local_time
+        }
+    }
+
+    fn weight(&self) -> f32 {
+        let mut weight = self.clip.weight;
+        if let Some(duration) = self.fade_in.filter(|d| *d > 0.0) {
+            weight *= (self.elapsed / duration).min(1.0);
+        }
+        if let Some((duration, elapsed)) = self.fade_out.filter(|(d, _)| *d > 0.0) {
+            weight *= (1.0 - elapsed / duration).max(0.0);
+        }
+        weight
+    }
+}
+
+///
+/// Plays a set of [AnimationClip]s at the same time with individual weights and playback speeds and supports cross-fading from one clip to another.
+/// Attach it to a [Model] or [InstancedModel] using for example [Model::animation_player_mut], the clips are then evaluated and blended when calling [Model::animate].
+///
+#[derive(Default)]
+pub struct AnimationPlayer {
+    clips: Vec<ActiveClip>,
+    last_time: Option<f32>,
+}
+
+impl AnimationPlayer {
+    ///
+    /// Creates a new animation player without any active clips.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Starts playing the given clip from the beginning in addition to the already active clips.
+    /// If a clip with the same name is already active, it is replaced.
+    ///
+    pub fn play(&mut self, clip: AnimationClip) {
+        self.clips.retain(|c| c.clip.name != clip.name);
+        self.restart_if_idle();
+        self.clips.push(ActiveClip::new(clip, None));
+    }
+
+    ///
+    /// Starts playing the given clip from the beginning and fades it in during the given duration (in the same unit as the time given to [Self::update])
+    /// while all other active clips are faded out during the same duration.
+    ///
+    pub fn cross_fade(&mut self, clip: AnimationClip, duration: f32) {
+        self.clips.retain(|c| c.clip.name != clip.name);
+        for active_clip in self.clips.iter_mut() {
+            if active_clip.fade_out.is_none() {
+                active_clip.fade_out = Some((duration, 0.0));
+            }
+        }
+        self.restart_if_idle();
+        self.clips.push(ActiveClip::new(clip, Some(duration)));
+    }
+
+    ///
+    /// Fades out the active clip with the given name during the given duration after which the clip is stopped.
+    ///
+    pub fn fade_out(&mut self, name: Option<&str>, duration: f32) {
+        if let Some(active_clip) = self
+            .clips
+            .iter_mut()
+            .find(|c| c.clip.name.as_deref() == name)
+        {
+            active_clip.fade_out = Some((duration, 0.0));
+        }
+    }
+
+    ///
+    /// Stops the active clip with the given name immediately.
+    ///
+    pub fn stop(&mut self, name: Option<&str>) {
+        self.clips.retain(|c| c.clip.name.as_deref() != name);
+    }
+
+    ///
+    /// Stops all active clips immediately.
+    ///
+    pub fn stop_all(&mut self) {
+        self.clips.clear();
+    }
+
+    ///
+    /// Returns whether or not any clips are active.
+    ///
+    pub fn is_playing(&self) -> bool {
+        !self.clips.is_empty()
+    }
+
+    ///
+    /// Returns the active clips.
+    ///
+    pub fn clips(&self) -> impl Iterator<Item = &AnimationClip> {
+        self.clips.iter().map(|c| &c.clip)
+    }
+
+    ///
+    /// Returns the active clip with the given name if any, which can be used to change for example the weight or speed of the clip during playback.
+    ///
+    pub fn clip_mut(&mut self, name: Option<&str>) -> Option<&mut AnimationClip> {
+        self.clips
+            .iter_mut()
+            .find(|c| c.clip.name.as_deref() == name)
+            .map(|c| &mut c.clip)
+    }
+
+    // The time between the last update and starting a clip while idle must not advance the new clip.
+    fn restart_if_idle(&mut self) {
+        if self.clips.is_empty() {
+            self.last_time = None;
+        }
+    }
+
+    ///
+    /// Advances the playback to the given time, which should be some continious time, for example the time since start,
+    /// and returns the time and weight of each active clip. Clips that are completely faded out are stopped.
+    /// When no clips are active, the next clip that is started begins at the time of the following update.
+    ///
+    pub fn update(&mut self, time: f32) -> Vec<AnimationSample> {
+        let elapsed_time = self.last_time.map(|t| time - t).unwrap_or(0.0);
+        self.last_time = Some(time);
+        for active_clip in self.clips.iter_mut() {
+            active_clip.local_time += elapsed_time * active_clip.clip.speed;
+            active_clip.elapsed += elapsed_time;
+            if let Some((_, elapsed)) = active_clip.fade_out.as_mut() {
+                *elapsed += elapsed_time;
+            }
+        }
+        self.clips.retain(|c| {
+            c.fade_out
+                .map(|(duration, elapsed)| elapsed < duration)
+                .unwrap_or(true)
+        });
+        self.clips
+            .iter()
+            .map(|c| AnimationSample {
+                name: c.clip.name.clone(),
+                time: c.animation_time(),
+                weight: c.weight(),
+            })
+            .filter(|s| s.weight > 0.0)
+            .collect()
+    }
+}
+
+///
+/// Evaluates the animations given by the [AnimationSample]s among the given key frame animations and blends the resulting transformations.
+/// Returns `None` if none of the given animations are sampled.
+///
+pub(in crate::renderer) fn blend_key_frame_animations(
+    animations: &[KeyFrameAnimation],
+    samples: &[AnimationSample],
+) -> Option<Mat4> {
+    blend_transformations(samples.iter().filter_map(|sample| {
+        animations
+            .iter()
+            .find(|a| sample.name == a.name)
+            .map(|a| (a.transformation(sample.time), sample.weight))
+    }))
+}
+
+///
+/// Blends the given transformations using the given weights by blending the translation, rotation and scale of each transformation.
+/// Returns `None` if the sum of the weights is zero.
+///
+pub(in crate::renderer) fn blend_transformations(
+    transformations: impl IntoIterator<Item = (Mat4, f32)>,
+) -> Option<Mat4> {
+    let mut total_weight = 0.0;
+    let mut translation = vec3(0.0, 0.0, 0.0);
+    let mut scale = vec3(0.0, 0.0, 0.0);
+    let mut rotation: Option<Quat> = None;
+    for (transformation, weight) in transformations {
+        if weight <= 0.0 {
+            continue;
+        }
+        let (t, mut r, s) = decompose_transformation(&transformation);
+        if let Some(rotation) = rotation {
+            if rotation.dot(r) < 0.0 {
+                r = -r;
+            }
+        }
+        total_weight += weight;
+        translation += weight * t;
+        scale += weight * s;
+        rotation = Some(rotation.map(|q| q + r * weight).unwrap_or(r * weight));
+    }
+    if total_weight <= 0.0 {
+        return None;
+    }
+    let rotation = rotation?.normalize();
+    Some(
+        Mat4::from_translation(translation / total_weight)
+            * Mat4::from(rotation)
+            * Mat4::from_nonuniform_scale(
+                scale.x / total_weight,
+                scale.y / total_weight,
+                scale.z / total_weight,
+            ),
+    )
+}
+
+///
+/// Splits the given transformation into a translation, a rotation and a scale, assuming that it does not contain any shear.
+///
+pub(in crate::renderer) fn decompose_transformation(transformation: &Mat4) -> (Vec3, Quat, Vec3) {
+    let scale = vec3(
+        transformation.x.truncate().magnitude(),
+        transformation.y.truncate().magnitude(),
+        transformation.z.truncate().magnitude(),
+    );
+    let rotation = Quat::from(Mat3::from_cols(
+        transformation.x.truncate() / scale.x.max(f32::EPSILON),
+        transformation.y.truncate() / scale.y.max(f32::EPSILON),
+        transformation.z.truncate() / scale.z.max(f32::EPSILON),
+    ));
+    (transformation.w.truncate(), rotation, scale)
+}
+
+///
+/// Blends the given morph target weights, one list of weights for each animation, using the given blend weights.
+/// Returns `None` if the sum of the blend weights is zero.
+///
+pub(in crate::renderer) fn blend_morph_weights(
+    weights: impl IntoIterator<Item = (Vec<f32>, f32)>,
+) -> Option<Vec<f32>> {
+    let mut total_weight = 0.0;
+    let mut result: Vec<f32> = Vec::new();
+    for (morph_weights, weight) in weights {
+        if weight <= 0.0 {
+            continue;
+        }
+        if result.len() < morph_weights.len() {
+            result.resize(morph_weights.len(), 0.0);
+        }
+        for (r, w) in result.iter_mut().zip(morph_weights.iter()) {
+            *r += weight * w;
+        }
+        total_weight += weight;
+    }
+    if total_weight <= 0.0 {
+        None
+    } else {
+        Some(result.into_iter().map(|w| w / total_weight).collect())
+    }
+}
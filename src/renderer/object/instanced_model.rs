@@ -2,10 +2,13 @@ use crate::renderer::*;
 
 ///
 /// Part of an [InstancedModel] consisting of a [InstancedMesh], some type of [material] and a set of possible animations.
+/// If a [Skin] is specified using [Self::set_skin], the mesh is deformed by the joints of the skin.
 ///
 pub struct InstancedModelPart<M: Material> {
     gm: Gm<InstancedMesh, M>,
     animations: Vec<KeyFrameAnimation>,
+    skin: Option<Skin>,
+    animation_name: Option<String>,
 }
 
 impl<M: Material> InstancedModelPart<M> {
@@ -13,23 +16,81 @@ impl<M: Material> InstancedModelPart<M> {
     /// Returns a list of unique names for the animations for this model part. Use these names as input to [Self::choose_animation].
     ///
     pub fn animations(&self) -> Vec<Option<String>> {
-        self.animations
+        let mut names = self
+            .animations
             .iter()
             .map(|animation| animation.name.clone())
-            .collect()
+            .collect::<Vec<_>>();
+        if let Some(skin) = &self.skin {
+            for name in skin.animations() {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
     }
 
     ///
     /// Specifies the animation to use when [Geometry::animate] is called. Use the [Self::animations] method to get a list of possible animations.
     ///
     pub fn choose_animation(&mut self, animation_name: Option<&str>) {
-        if let Some(animation) = self
-            .animations
-            .iter()
-            .find(|a| animation_name == a.name.as_deref())
-            .cloned()
-        {
-            self.set_animation(move |time| animation.transformation(time));
+        self.animation_name = animation_name.map(|name| name.to_owned());
+    }
+
+    ///
+    /// Returns the [Skin] used for deforming this model part if any.
+    ///
+    pub fn skin(&self) -> Option<&Skin> {
+        self.skin.as_ref()
+    }
+
+    ///
+    /// Specifies the [Skin] used for deforming this model part together with the joint indices and weights for each vertex (see [InstancedMesh::set_skinning_attributes]).
+    /// The joints are animated using the animation chosen by [Self::choose_animation] when [Geometry::animate] is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of joints or weights does not match the number of vertices or if the skin has more than [MAX_JOINTS] joints.
+    pub fn set_skin(&mut self, skin: Skin, joints: &[Vector4<u16>], weights: &[Vec4]) {
+        self.gm.geometry.set_skinning_attributes(joints, weights);
+        self.gm.geometry.set_joint_transformations(
+            &skin.joint_transformations(self.animation_name.as_deref(), 0.0),
+        );
+        self.skin = Some(skin);
+    }
+
+    fn animate_blended(&mut self, time: f32, samples: &[AnimationSample]) {
+        if self.animations.is_empty() {
+            self.gm.animate(time);
+        } else {
+            self.gm.geometry.play_instance_animation_clips(samples);
+        }
+        if let Some(skin) = &self.skin {
+            let instances = self.gm.geometry.instances();
+            if instances.has_animation_times() {
+                let joint_transformations = (0..instances.count() as usize)
+                    .map(|i| {
+                        skin.blended_joint_transformations(
+                            &samples
+                                .iter()
+                                .map(|sample| AnimationSample {
+                                    name: sample.name.clone(),
+                                    time: instances.animation_time(i, sample.time),
+                                    weight: sample.weight,
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.gm
+                    .geometry
+                    .set_instance_joint_transformations(&joint_transformations);
+            } else {
+                self.gm
+                    .geometry
+                    .set_joint_transformations(&skin.blended_joint_transformations(samples));
+            }
         }
     }
 }
@@ -61,7 +122,37 @@ impl<M: Material> Geometry for InstancedModelPart<M> {
     impl_geometry_body!(deref);
 
     fn animate(&mut self, time: f32) {
-        self.gm.animate(time)
+        if self.animations.is_empty() {
+            self.gm.animate(time);
+        } else {
+            self.gm
+                .geometry
+                .play_instance_animation_clips(&[AnimationSample {
+                    name: self.animation_name.clone(),
+                    time,
+                    weight: 1.0,
+                }]);
+        }
+        if let Some(skin) = &self.skin {
+            let instances = self.gm.geometry.instances();
+            if instances.has_animation_times() {
+                let joint_transformations = (0..instances.count() as usize)
+                    .map(|i| {
+                        skin.joint_transformations(
+                            self.animation_name.as_deref(),
+                            instances.animation_time(i, time),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.gm
+                    .geometry
+                    .set_instance_joint_transformations(&joint_transformations);
+            } else {
+                self.gm.geometry.set_joint_transformations(
+                    &skin.joint_transformations(self.animation_name.as_deref(), time),
+                );
+            }
+        }
     }
 }
 
@@ -72,7 +163,10 @@ impl<M: Material> Object for InstancedModelPart<M> {
 ///
 /// Similar to [Model], except it is possible to render many instances of the same model efficiently.
 ///
-pub struct InstancedModel<M: Material>(Vec<InstancedModelPart<M>>);
+pub struct InstancedModel<M: Material> {
+    parts: Vec<InstancedModelPart<M>>,
+    animation_player: AnimationPlayer,
+}
 
 impl<'a, M: Material> IntoIterator for &'a InstancedModel<M> {
     type Item = &'a dyn Object;
@@ -90,6 +184,7 @@ impl<M: Material + FromCpuMaterial + Clone + Default> InstancedModel<M> {
     ///
     /// Constructs an [InstancedModel] from a [CpuModel] and the given [Instances] attributes, ie. constructs a list of [Gm]s with a [InstancedMesh] as geometry (constructed from the [CpuMesh]es in the [CpuModel]) and
     /// a [material] type specified by the generic parameter which implement [FromCpuMaterial] (constructed from the [CpuMaterial]s in the [CpuModel]).
+    /// If a primitive in the [CpuModel] has a [CpuSkin] and its mesh has joint indices and weights, the corresponding [Skin] is set on the model part.
     ///
     pub fn new(
         context: &Context,
@@ -122,13 +217,33 @@ impl<M: Material + FromCpuMaterial + Clone + Default> InstancedModel<M> {
                     material,
                 };
                 gm.set_transformation(primitive.transformation);
-                gms.push(InstancedModelPart {
+                if !primitive.animations.is_empty() {
+                    gm.geometry.set_instance_animation_clips(
+                        primitive
+                            .animations
+                            .iter()
+                            .map(InstanceAnimationClip::from)
+                            .collect(),
+                    );
+                }
+                let mut part = InstancedModelPart {
                     gm,
                     animations: primitive.animations.clone(),
-                });
+                    skin: None,
+                    animation_name: None,
+                };
+                if let (Some(skin), Some(joints), Some(weights)) =
+                    (&primitive.skin, &geometry.joints, &geometry.weights)
+                {
+                    part.set_skin(Skin::from(skin), joints, weights);
+                }
+                gms.push(part);
             }
         }
-        let mut model = Self(gms);
+        let mut model = Self {
+            parts: gms,
+            animation_player: AnimationPlayer::new(),
+        };
         if let Some(animation_name) = model.animations().first().cloned() {
             model.choose_animation(animation_name.as_deref());
         }
@@ -140,7 +255,7 @@ impl<M: Material + FromCpuMaterial + Clone + Default> InstancedModel<M> {
     ///
     pub fn animations(&self) -> Vec<Option<String>> {
         let mut set = std::collections::HashSet::new();
-        for model_part in self.0.iter() {
+        for model_part in self.parts.iter() {
             set.extend(model_part.animations());
         }
         set.into_iter().collect()
@@ -150,28 +265,50 @@ impl<M: Material + FromCpuMaterial + Clone + Default> InstancedModel<M> {
     /// Specifies the animation to use when [Geometry::animate] is called. Use the [Self::animations] method to get a list of possible animations.
     ///
     pub fn choose_animation(&mut self, animation_name: Option<&str>) {
-        for part in self.0.iter_mut() {
+        for part in self.parts.iter_mut() {
             part.choose_animation(animation_name);
         }
     }
 
+    ///
+    /// Returns the [AnimationPlayer] used for blending and cross-fading between the animations of this model.
+    ///
+    pub fn animation_player(&self) -> &AnimationPlayer {
+        &self.animation_player
+    }
+
+    ///
+    /// Returns a mutable reference to the [AnimationPlayer] used for blending and cross-fading between the animations of this model.
+    /// As long as the player has active clips, these are used when calling [Self::animate] instead of the animation chosen by [Self::choose_animation].
+    ///
+    pub fn animation_player_mut(&mut self) -> &mut AnimationPlayer {
+        &mut self.animation_player
+    }
+
     ///
     /// For updating the animation. The time parameter should be some continious time, for example the time since start.
     ///
     pub fn animate(&mut self, time: f32) {
-        self.iter_mut().for_each(|m| m.animate(time));
+        if self.animation_player.is_playing() {
+            let samples = self.animation_player.update(time);
+            self.parts
+                .iter_mut()
+                .for_each(|m| m.animate_blended(time, &samples));
+        } else {
+            self.iter_mut().for_each(|m| m.animate(time));
+        }
     }
 }
 
 impl<M: Material> std::ops::Deref for InstancedModel<M> {
     type Target = Vec<InstancedModelPart<M>>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.parts
     }
 }
 
 impl<M: Material> std::ops::DerefMut for InstancedModel<M> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.parts
     }
 }
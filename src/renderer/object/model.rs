@@ -3,10 +3,15 @@ pub use three_d_asset::Model as CpuModel;
 
 ///
 /// Part of a [Model] consisting of a [Mesh], some type of [material] and a set of possible animations.
+/// If a [Skin] is specified using [Self::set_skin], the mesh is deformed by the joints of the skin
+/// and if morph targets are specified using [Self::set_morph_targets], the mesh is deformed by the animated morph target weights.
 ///
 pub struct ModelPart<M: Material> {
     gm: Gm<Mesh, M>,
     animations: Vec<KeyFrameAnimation>,
+    skin: Option<Skin>,
+    morph_animations: Vec<MorphAnimation>,
+    animation_name: Option<String>,
 }
 
 impl<M: Material> ModelPart<M> {
@@ -14,10 +19,25 @@ impl<M: Material> ModelPart<M> {
     /// Returns a list of unique names for the animations for this model part. Use these names as input to [Self::choose_animation].
     ///
     pub fn animations(&self) -> Vec<Option<String>> {
-        self.animations
+        let mut names = self
+            .animations
             .iter()
             .map(|animation| animation.name.clone())
-            .collect()
+            .collect::<Vec<_>>();
+        if let Some(skin) = &self.skin {
+            for name in skin.animations() {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        for animation in self.morph_animations.iter() {
+            let name = animation.name().map(|name| name.to_owned());
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
     }
 
     ///
@@ -32,6 +52,82 @@ impl<M: Material> ModelPart<M> {
         {
             self.set_animation(move |time| animation.transformation(time));
         }
+        self.animation_name = animation_name.map(|name| name.to_owned());
+    }
+
+    ///
+    /// Returns the [Skin] used for deforming this model part if any.
+    ///
+    pub fn skin(&self) -> Option<&Skin> {
+        self.skin.as_ref()
+    }
+
+    ///
+    /// Specifies the [Skin] used for deforming this model part together with the joint indices and weights for each vertex (see [Mesh::set_skinning_attributes]).
+    /// The joints are animated using the animation chosen by [Self::choose_animation] when [Geometry::animate] is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of joints or weights does not match the number of vertices or if the skin has more than [MAX_JOINTS] joints.
+    pub fn set_skin(&mut self, skin: Skin, joints: &[Vector4<u16>], weights: &[Vec4]) {
+        self.gm.geometry.set_skinning_attributes(joints, weights);
+        self.gm.geometry.set_joint_transformations(
+            &skin.joint_transformations(self.animation_name.as_deref(), 0.0),
+        );
+        self.skin = Some(skin);
+    }
+
+    ///
+    /// Specifies the morph targets of the mesh in this model part (see [Mesh::set_morph_targets]) together with a set of possible animations of the morph target weights.
+    /// The morph target weights are animated using the animation chosen by [Self::choose_animation] when [Geometry::animate] is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [MAX_MORPH_TARGETS] morph targets are given or if the number of positions or normals in a morph target does not match the number of vertices in the mesh.
+    pub fn set_morph_targets(
+        &mut self,
+        morph_targets: &[MorphTarget],
+        morph_animations: Vec<MorphAnimation>,
+    ) {
+        self.gm.geometry.set_morph_targets(morph_targets);
+        self.morph_animations = morph_animations;
+        self.animate_morph_weights(0.0);
+    }
+
+    fn animate_morph_weights(&mut self, time: f32) {
+        if let Some(animation) = self
+            .morph_animations
+            .iter()
+            .find(|a| self.animation_name.as_deref() == a.name())
+        {
+            let weights = animation.weights(time);
+            if weights.len() as u32 == self.gm.geometry.morph_target_count() {
+                self.gm.geometry.set_morph_weights(&weights);
+            }
+        }
+    }
+
+    fn animate_blended(&mut self, time: f32, samples: &[AnimationSample]) {
+        if let Some(animation) = blend_key_frame_animations(&self.animations, samples) {
+            self.gm.geometry.set_animation_transformation(animation);
+        } else {
+            self.gm.animate(time);
+        }
+        if let Some(skin) = &self.skin {
+            self.gm
+                .geometry
+                .set_joint_transformations(&skin.blended_joint_transformations(samples));
+        }
+        if let Some(weights) = blend_morph_weights(samples.iter().filter_map(|sample| {
+            self.morph_animations
+                .iter()
+                .find(|a| sample.name.as_deref() == a.name())
+                .map(|a| (a.weights(sample.time), sample.weight))
+        })) {
+            if weights.len() as u32 == self.gm.geometry.morph_target_count() {
+                self.gm.geometry.set_morph_weights(&weights);
+            }
+        }
     }
 }
 
@@ -53,7 +149,13 @@ impl<M: Material> Geometry for ModelPart<M> {
     impl_geometry_body!(deref);
 
     fn animate(&mut self, time: f32) {
-        self.gm.animate(time)
+        self.gm.animate(time);
+        if let Some(skin) = &self.skin {
+            self.gm.geometry.set_joint_transformations(
+                &skin.joint_transformations(self.animation_name.as_deref(), time),
+            );
+        }
+        self.animate_morph_weights(time);
     }
 }
 
@@ -73,7 +175,10 @@ impl<'a, M: Material> IntoIterator for &'a ModelPart<M> {
 ///
 /// A 3D model consisting of a set of [Gm]s with [Mesh]es as the geometries and a [material] type specified by the generic parameter.
 ///
-pub struct Model<M: Material>(Vec<ModelPart<M>>);
+pub struct Model<M: Material> {
+    parts: Vec<ModelPart<M>>,
+    animation_player: AnimationPlayer,
+}
 
 impl<'a, M: Material> IntoIterator for &'a Model<M> {
     type Item = &'a dyn Object;
@@ -91,6 +196,8 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     ///
     /// Constructs a [Model] from a [CpuModel], ie. constructs a list of [Gm]s with a [Mesh] as geometry (constructed from the [CpuMesh]es in the [CpuModel]) and
     /// a [material] type specified by the generic parameter which implement [FromCpuMaterial] (constructed from the [CpuMaterial]s in the [CpuModel]).
+    /// If a primitive in the [CpuModel] has a [CpuSkin] and its mesh has joint indices and weights, the corresponding [Skin] is set on the model part.
+    /// If its mesh has morph targets, these are set on the model part together with the weight animations of the primitive (see [ModelPart::set_morph_targets]).
     ///
     pub fn new(context: &Context, cpu_model: &CpuModel) -> Result<Self, RendererError> {
         let materials = cpu_model
@@ -119,13 +226,50 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
                     material,
                 };
                 gm.set_transformation(primitive.transformation);
-                gms.push(ModelPart {
+                let mut part = ModelPart {
                     gm,
                     animations: primitive.animations.clone(),
-                });
+                    skin: None,
+                    morph_animations: Vec::new(),
+                    animation_name: None,
+                };
+                if let (Some(skin), Some(joints), Some(weights)) =
+                    (&primitive.skin, &geometry.joints, &geometry.weights)
+                {
+                    part.set_skin(Skin::from(skin), joints, weights);
+                }
+                if let Some(morph_targets) = &geometry.morph_targets {
+                    let mut morph_animations = Vec::new();
+                    for animation in primitive.animations.iter() {
+                        for (_, key_frames) in animation.key_frames.iter() {
+                            if let Some(weights) = &key_frames.weights {
+                                morph_animations.push(MorphAnimation::new(
+                                    animation.name.clone(),
+                                    key_frames.loop_time,
+                                    key_frames.times.clone(),
+                                    weights.clone(),
+                                )?);
+                            }
+                        }
+                    }
+                    part.set_morph_targets(
+                        &morph_targets
+                            .iter()
+                            .map(|morph_target| MorphTarget {
+                                positions: morph_target.positions.clone(),
+                                normals: morph_target.normals.clone(),
+                            })
+                            .collect::<Vec<_>>(),
+                        morph_animations,
+                    );
+                }
+                gms.push(part);
             }
         }
-        let mut model = Self(gms);
+        let mut model = Self {
+            parts: gms,
+            animation_player: AnimationPlayer::new(),
+        };
         if let Some(animation_name) = model.animations().first().cloned() {
             model.choose_animation(animation_name.as_deref());
         }
@@ -137,7 +281,7 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     ///
     pub fn animations(&self) -> Vec<Option<String>> {
         let mut set = std::collections::HashSet::new();
-        for model_part in self.0.iter() {
+        for model_part in self.parts.iter() {
             set.extend(model_part.animations());
         }
         set.into_iter().collect()
@@ -147,28 +291,50 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     /// Specifies the animation to use when [Geometry::animate] is called. Use the [Self::animations] method to get a list of possible animations.
     ///
     pub fn choose_animation(&mut self, animation_name: Option<&str>) {
-        for part in self.0.iter_mut() {
+        for part in self.parts.iter_mut() {
             part.choose_animation(animation_name);
         }
     }
 
+    ///
+    /// Returns the [AnimationPlayer] used for blending and cross-fading between the animations of this model.
+    ///
+    pub fn animation_player(&self) -> &AnimationPlayer {
+        &self.animation_player
+    }
+
+    ///
+    /// Returns a mutable reference to the [AnimationPlayer] used for blending and cross-fading between the animations of this model.
+    /// As long as the player has active clips, these are used when calling [Self::animate] instead of the animation chosen by [Self::choose_animation].
+    ///
+    pub fn animation_player_mut(&mut self) -> &mut AnimationPlayer {
+        &mut self.animation_player
+    }
+
     ///
     /// For updating the animation. The time parameter should be some continious time, for example the time since start.
     ///
     pub fn animate(&mut self, time: f32) {
-        self.iter_mut().for_each(|m| m.animate(time));
+        if self.animation_player.is_playing() {
+            let samples = self.animation_player.update(time);
+            self.parts
+                .iter_mut()
+                .for_each(|m| m.animate_blended(time, &samples));
+        } else {
+            self.iter_mut().for_each(|m| m.animate(time));
+        }
     }
 }
 
 impl<M: Material> std::ops::Deref for Model<M> {
     type Target = Vec<ModelPart<M>>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.parts
     }
 }
 
 impl<M: Material> std::ops::DerefMut for Model<M> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.parts
     }
 }
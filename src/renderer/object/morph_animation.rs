@@ -0,0 +1,73 @@
+use crate::renderer::*;
+
+///
+/// An animation of the morph target weights of a [Mesh], for example from a glTF weight animation channel.
+/// The weights are linearly interpolated between the key frames.
+///
+#[derive(Clone, Debug, Default)]
+pub struct MorphAnimation {
+    name: Option<String>,
+    loop_time: Option<f32>,
+    times: Vec<f32>,
+    weights: Vec<Vec<f32>>,
+}
+
+impl MorphAnimation {
+    ///
+    /// Creates a new morph animation with the given name, which is used to choose the animation together with the other animations of a [Model].
+    /// The `times` are the time of each key frame in increasing order and `weights` contains the weight of each morph target for each key frame.
+    /// If `loop_time` is specified, the animation is looping at that time.
+    ///
+    /// Returns an error if the number of weights does not match the number of key frames.
+    ///
+    pub fn new(
+        name: Option<String>,
+        loop_time: Option<f32>,
+        times: Vec<f32>,
+        weights: Vec<Vec<f32>>,
+    ) -> Result<Self, RendererError> {
+        if weights.len() != times.len() {
+            Err(RendererError::InvalidBufferLength(
+                "morph animation weights".to_string(),
+                times.len(),
+                weights.len(),
+            ))?;
+        }
+        Ok(Self {
+            name,
+            loop_time,
+            times,
+            weights,
+        })
+    }
+
+    ///
+    /// Returns the name of this animation.
+    ///
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    ///
+    /// Returns the weight of each morph target at the given time.
+    ///
+    pub fn weights(&self, time: f32) -> Vec<f32> {
+        let time = self.loop_time.map(|t| time % t).unwrap_or(time);
+        if self.times.is_empty() {
+            return Vec::new();
+        }
+        let index = self.times.partition_point(|t| *t <= time);
+        if index == 0 {
+            return self.weights[0].clone();
+        }
+        if index >= self.times.len() {
+            return self.weights[self.times.len() - 1].clone();
+        }
+        let t = (time - self.times[index - 1]) / (self.times[index] - self.times[index - 1]);
+        self.weights[index - 1]
+            .iter()
+            .zip(self.weights[index].iter())
+            .map(|(w0, w1)| w0 + (w1 - w0) * t)
+            .collect()
+    }
+}
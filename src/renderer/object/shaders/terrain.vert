@@ -5,6 +5,7 @@ in vec3 position;
 out vec3 pos;
 out vec2 uvs;
 out vec4 col;
+flat out int instance_id;
 
 #ifdef USE_NORMALS
 
@@ -21,6 +22,7 @@ void main()
     pos = worldPos.xyz;
     uvs = worldPos.xz;
     col = vec4(1.0);
+    instance_id = 0;
 #ifdef USE_NORMALS
     nor = normalize(normal);
     tang = cross(vec3(1.0, 0.0, 0.0), nor);
@@ -14,6 +14,7 @@ out vec2 uvs;
 out vec3 nor;
 out vec3 pos;
 out vec4 col;
+flat out int instance_id;
 
 void main()
 {
@@ -54,4 +55,5 @@ void main()
     gl_Position = viewProjection * vec4(pos, 1.);
     uvs = pos.xz;
     col = vec4(1.0);
+    instance_id = 0;
 }
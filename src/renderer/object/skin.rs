@@ -0,0 +1,133 @@
+use crate::renderer::*;
+pub use three_d_asset::{Joint as CpuJoint, Skin as CpuSkin};
+
+///
+/// A joint (or bone) in a [Skin].
+///
+#[derive(Clone)]
+pub struct Joint {
+    /// The name of the joint.
+    pub name: Option<String>,
+    /// The index of the parent joint in [Skin::joints] or `None` if this is a root joint.
+    pub parent: Option<usize>,
+    /// The transformation of this joint relative to the parent joint which is used when none of the [Joint::animations] is chosen.
+    pub transformation: Mat4,
+    /// The inverse bind matrix which transforms from the mesh space to the local space of this joint in the bind pose.
+    pub inverse_bind_matrix: Mat4,
+    /// A set of possible animations, each of which returns the transformation of this joint relative to the parent joint at a given time.
+    pub animations: Vec<KeyFrameAnimation>,
+}
+
+///
+/// A skeleton used for deforming a [Mesh] or an [InstancedMesh], for example the part of an animated character.
+/// The joint transformations are evaluated from the animations of each joint and then sent to the mesh using [Mesh::set_joint_transformations].
+///
+#[derive(Clone, Default)]
+pub struct Skin {
+    /// The joints of the skeleton. The joint indices specified in [Mesh::set_skinning_attributes] refers to indices into this list.
+    pub joints: Vec<Joint>,
+}
+
+impl From<&CpuSkin> for Skin {
+    fn from(cpu_skin: &CpuSkin) -> Self {
+        Self {
+            joints: cpu_skin
+                .joints
+                .iter()
+                .map(|joint| Joint {
+                    name: joint.name.clone(),
+                    parent: joint.parent,
+                    transformation: joint.transformation,
+                    inverse_bind_matrix: joint.inverse_bind_matrix,
+                    animations: joint.animations.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Skin {
+    ///
+    /// Returns a list of unique names for the animations of the joints in this skin.
+    ///
+    pub fn animations(&self) -> Vec<Option<String>> {
+        let mut names = Vec::new();
+        for animation in self.joints.iter().flat_map(|joint| joint.animations.iter()) {
+            if !names.contains(&animation.name) {
+                names.push(animation.name.clone());
+            }
+        }
+        names
+    }
+
+    ///
+    /// Evaluates the animation with the given name at the given time and returns the global transformation of each joint multiplied by the inverse bind matrix.
+    /// Joints without an animation with the given name use their [Joint::transformation].
+    ///
+    pub fn joint_transformations(&self, animation_name: Option<&str>, time: f32) -> Vec<Mat4> {
+        let local_transformations = self
+            .joints
+            .iter()
+            .map(|joint| {
+                joint
+                    .animations
+                    .iter()
+                    .find(|a| animation_name == a.name.as_deref())
+                    .map(|a| a.transformation(time))
+                    .unwrap_or(joint.transformation)
+            })
+            .collect::<Vec<_>>();
+        self.joint_transformations_from_local(&local_transformations)
+    }
+
+    ///
+    /// Evaluates and blends the animations given by the [AnimationSample]s (see [AnimationPlayer::update]) and returns the global transformation of each joint multiplied by the inverse bind matrix.
+    /// Joints without any of the animations use their [Joint::transformation].
+    ///
+    pub fn blended_joint_transformations(&self, samples: &[AnimationSample]) -> Vec<Mat4> {
+        let local_transformations = self
+            .joints
+            .iter()
+            .map(|joint| {
+                blend_transformations(samples.iter().filter_map(|sample| {
+                    joint
+                        .animations
+                        .iter()
+                        .find(|a| sample.name == a.name)
+                        .map(|a| (a.transformation(sample.time), sample.weight))
+                }))
+                .unwrap_or(joint.transformation)
+            })
+            .collect::<Vec<_>>();
+        self.joint_transformations_from_local(&local_transformations)
+    }
+
+    fn joint_transformations_from_local(&self, local_transformations: &[Mat4]) -> Vec<Mat4> {
+        let mut global_transformations = vec![None; self.joints.len()];
+        (0..self.joints.len())
+            .map(|i| {
+                self.global_transformation(i, local_transformations, &mut global_transformations)
+                    * self.joints[i].inverse_bind_matrix
+            })
+            .collect()
+    }
+
+    fn global_transformation(
+        &self,
+        index: usize,
+        local_transformations: &[Mat4],
+        global_transformations: &mut [Option<Mat4>],
+    ) -> Mat4 {
+        if let Some(transformation) = global_transformations[index] {
+            return transformation;
+        }
+        let transformation = if let Some(parent) = self.joints[index].parent {
+            self.global_transformation(parent, local_transformations, global_transformations)
+                * local_transformations[index]
+        } else {
+            local_transformations[index]
+        };
+        global_transformations[index] = Some(transformation);
+        transformation
+    }
+}
@@ -402,4 +402,8 @@ impl Geometry for TerrainPatch {
     fn aabb(&self) -> AxisAlignedBoundingBox {
         self.aabb
     }
+
+    fn outputs_instance_id(&self) -> bool {
+        true
+    }
 }
@@ -298,6 +298,10 @@ impl Geometry for WaterPatch {
         ])
     }
 
+    fn outputs_instance_id(&self) -> bool {
+        true
+    }
+
     fn animate(&mut self, time: f32) {
         self.time = time;
     }
@@ -6,7 +6,6 @@ use instant::Instant;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 use winit::dpi::PhysicalSize;
-use winit::event::TouchPhase;
 use winit::event::WindowEvent;
 
 ///
@@ -24,10 +23,10 @@ pub struct FrameInputGenerator {
     device_pixel_ratio: f64,
     cursor_pos: Option<LogicalPoint>,
     finger_id: Option<u64>,
-    secondary_cursor_pos: Option<LogicalPoint>,
-    secondary_finger_id: Option<u64>,
+    fingers: Vec<(u64, LogicalPoint)>,
     modifiers: Modifiers,
     mouse_pressed: Option<MouseButton>,
+    pressed_keys: Vec<Key>,
 }
 
 impl FrameInputGenerator {
@@ -48,10 +47,10 @@ impl FrameInputGenerator {
             last_time: Instant::now(),
             cursor_pos: None,
             finger_id: None,
-            secondary_cursor_pos: None,
-            secondary_finger_id: None,
+            fingers: Vec::new(),
             modifiers: Modifiers::default(),
             mouse_pressed: None,
+            pressed_keys: Vec::new(),
         }
     }
 
@@ -140,6 +139,10 @@ impl FrameInputGenerator {
                     use winit::event::VirtualKeyCode;
                     let state = input.state == winit::event::ElementState::Pressed;
                     if let Some(kind) = translate_virtual_key_code(keycode) {
+                        self.pressed_keys.retain(|k| *k != kind);
+                        if state {
+                            self.pressed_keys.push(kind);
+                        }
                         self.events.push(if state {
                             crate::Event::KeyPress {
                                 kind,
@@ -265,6 +268,22 @@ impl FrameInputGenerator {
                     self.events.push(crate::Event::Text(ch.to_string()));
                 }
             }
+            WindowEvent::Focused(false) => {
+                // The key releases are not received when the window is not focused, so release all keys and modifiers
+                for kind in self.pressed_keys.drain(..) {
+                    self.events.push(crate::Event::KeyRelease {
+                        kind,
+                        modifiers: Modifiers::default(),
+                        handled: false,
+                    });
+                }
+                if self.modifiers != Modifiers::default() {
+                    self.modifiers = Modifiers::default();
+                    self.events.push(crate::Event::ModifiersChange {
+                        modifiers: self.modifiers,
+                    });
+                }
+            }
             WindowEvent::CursorEntered { .. } => {
                 self.events.push(crate::Event::MouseEnter);
             }
@@ -280,9 +299,23 @@ impl FrameInputGenerator {
                     device_pixel_ratio: self.device_pixel_ratio as f32,
                     height: self.viewport.height as f32,
                 };
-                match touch.phase {
+                let phase = match touch.phase {
+                    winit::event::TouchPhase::Started => TouchPhase::Started,
+                    winit::event::TouchPhase::Moved => TouchPhase::Moved,
+                    winit::event::TouchPhase::Ended => TouchPhase::Ended,
+                    winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+                };
+                self.events.push(crate::Event::Touch {
+                    id: touch.id,
+                    phase,
+                    position: position.into(),
+                    modifiers: self.modifiers,
+                    handled: false,
+                });
+                match phase {
                     TouchPhase::Started => {
-                        if self.finger_id.is_none() {
+                        // Only the first finger emulates the mouse
+                        if self.fingers.is_empty() && self.finger_id.is_none() {
                             self.events.push(crate::Event::MousePress {
                                 button: MouseButton::Left,
                                 position: position.into(),
@@ -291,10 +324,8 @@ impl FrameInputGenerator {
                             });
                             self.cursor_pos = Some(position);
                             self.finger_id = Some(touch.id);
-                        } else if self.secondary_finger_id.is_none() {
-                            self.secondary_cursor_pos = Some(position);
-                            self.secondary_finger_id = Some(touch.id);
                         }
+                        self.fingers.push((touch.id, position));
                     }
                     TouchPhase::Ended | TouchPhase::Cancelled => {
                         if self.finger_id.map(|id| id == touch.id).unwrap_or(false) {
@@ -306,56 +337,32 @@ impl FrameInputGenerator {
                             });
                             self.cursor_pos = None;
                             self.finger_id = None;
-                        } else if self
-                            .secondary_finger_id
-                            .map(|id| id == touch.id)
-                            .unwrap_or(false)
-                        {
-                            self.secondary_cursor_pos = None;
-                            self.secondary_finger_id = None;
                         }
+                        self.fingers.retain(|(id, _)| *id != touch.id);
                     }
                     TouchPhase::Moved => {
-                        if self.finger_id.map(|id| id == touch.id).unwrap_or(false) {
-                            let last_pos = self.cursor_pos.unwrap();
-                            if let Some(p) = self.secondary_cursor_pos {
-                                self.events.push(crate::Event::MouseWheel {
-                                    position: position.into(),
-                                    modifiers: self.modifiers,
-                                    handled: false,
-                                    delta: (
-                                        (position.x - p.x).abs() - (last_pos.x - p.x).abs(),
-                                        (position.y - p.y).abs() - (last_pos.y - p.y).abs(),
-                                    ),
-                                });
-                            } else {
-                                self.events.push(crate::Event::MouseMotion {
-                                    button: Some(MouseButton::Left),
-                                    position: position.into(),
-                                    modifiers: self.modifiers,
-                                    handled: false,
-                                    delta: (position.x - last_pos.x, position.y - last_pos.y),
-                                });
-                            }
-                            self.cursor_pos = Some(position);
-                        } else if self
-                            .secondary_finger_id
-                            .map(|id| id == touch.id)
-                            .unwrap_or(false)
+                        if let Some(index) = self.fingers.iter().position(|(id, _)| *id == touch.id)
                         {
-                            let last_pos = self.secondary_cursor_pos.unwrap();
-                            if let Some(p) = self.cursor_pos {
-                                self.events.push(crate::Event::MouseWheel {
-                                    position: p.into(),
-                                    modifiers: self.modifiers,
-                                    handled: false,
-                                    delta: (
-                                        (position.x - p.x).abs() - (last_pos.x - p.x).abs(),
-                                        (position.y - p.y).abs() - (last_pos.y - p.y).abs(),
-                                    ),
-                                });
+                            let last_pos = self.fingers[index].1;
+                            self.fingers[index].1 = position;
+                            if self.fingers.len() == 1 {
+                                if self.finger_id.map(|id| id == touch.id).unwrap_or(false) {
+                                    self.events.push(crate::Event::MouseMotion {
+                                        button: Some(MouseButton::Left),
+                                        position: position.into(),
+                                        modifiers: self.modifiers,
+                                        handled: false,
+                                        delta: (position.x - last_pos.x, position.y - last_pos.y),
+                                    });
+                                }
+                            } else if index < 2 {
+                                // Gestures are generated from the first two fingers touching the screen
+                                let other = self.fingers[1 - index].1;
+                                self.push_gesture_events(last_pos, position, other);
+                            }
+                            if self.finger_id.map(|id| id == touch.id).unwrap_or(false) {
+                                self.cursor_pos = Some(position);
                             }
-                            self.secondary_cursor_pos = Some(position);
                         }
                     }
                 }
@@ -363,6 +370,54 @@ impl FrameInputGenerator {
             _ => (),
         }
     }
+
+    ///
+    /// Pushes the gesture events generated when one of two fingers moves from `last_pos` to `position`
+    /// while the other finger is at `other`.
+    ///
+    fn push_gesture_events(
+        &mut self,
+        last_pos: LogicalPoint,
+        position: LogicalPoint,
+        other: LogicalPoint,
+    ) {
+        let center = LogicalPoint {
+            x: 0.5 * (position.x + other.x),
+            y: 0.5 * (position.y + other.y),
+            ..position
+        };
+        let last_center = (0.5 * (last_pos.x + other.x), 0.5 * (last_pos.y + other.y));
+        let distance = (position.x - other.x).hypot(position.y - other.y);
+        let last_distance = (last_pos.x - other.x).hypot(last_pos.y - other.y);
+        // The y-axis of logical points points downwards, so the angles are negated to be counter-clockwise on the screen
+        let angle = -(position.y - other.y).atan2(position.x - other.x);
+        let last_angle = -(last_pos.y - other.y).atan2(last_pos.x - other.x);
+        let mut angle_delta = angle - last_angle;
+        if angle_delta > std::f32::consts::PI {
+            angle_delta -= 2.0 * std::f32::consts::PI;
+        } else if angle_delta < -std::f32::consts::PI {
+            angle_delta += 2.0 * std::f32::consts::PI;
+        }
+
+        self.events.push(crate::Event::Pinch {
+            delta: distance - last_distance,
+            position: center.into(),
+            modifiers: self.modifiers,
+            handled: false,
+        });
+        self.events.push(crate::Event::TwoFingerPan {
+            delta: (center.x - last_center.0, center.y - last_center.1),
+            position: center.into(),
+            modifiers: self.modifiers,
+            handled: false,
+        });
+        self.events.push(crate::Event::Rotate {
+            angle: angle_delta,
+            position: center.into(),
+            modifiers: self.modifiers,
+            handled: false,
+        });
+    }
 }
 
 fn is_printable_char(chr: char) -> bool {